@@ -0,0 +1,6 @@
+#!/bin/bash
+set -e
+
+RUSTFLAGS='-C link-arg=-s' cargo build --target wasm32-unknown-unknown --release -p quizchain-mock
+mkdir -p ../out
+cp ../target/wasm32-unknown-unknown/release/quizchain_mock.wasm ../out/mock.wasm
@@ -0,0 +1,62 @@
+// Fungible token, NFT and registry views used by eligibility gates of quizzes in local tests
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::{near_bindgen, setup_alloc, AccountId, BorshStorageKey, PanicOnDefault};
+
+setup_alloc!();
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    FtBalances,
+    NftSupplies,
+    RegisteredAccounts,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Mock {
+    ft_balances: LookupMap<AccountId, u128>,
+    nft_supplies: LookupMap<AccountId, u128>,
+    registered_accounts: LookupSet<AccountId>,
+}
+
+#[near_bindgen]
+impl Mock {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            ft_balances: LookupMap::new(StorageKey::FtBalances),
+            nft_supplies: LookupMap::new(StorageKey::NftSupplies),
+            registered_accounts: LookupSet::new(StorageKey::RegisteredAccounts),
+        }
+    }
+
+    pub fn set_ft_balance(&mut self, account_id: ValidAccountId, balance: U128) {
+        self.ft_balances.insert(account_id.as_ref(), &balance.0);
+    }
+
+    pub fn ft_balance_of(&self, account_id: ValidAccountId) -> U128 {
+        self.ft_balances.get(account_id.as_ref()).unwrap_or(0).into()
+    }
+
+    pub fn set_nft_supply(&mut self, account_id: ValidAccountId, supply: U128) {
+        self.nft_supplies.insert(account_id.as_ref(), &supply.0);
+    }
+
+    pub fn nft_supply_for_owner(&self, account_id: ValidAccountId) -> U128 {
+        self.nft_supplies.get(account_id.as_ref()).unwrap_or(0).into()
+    }
+
+    pub fn register(&mut self, account_id: ValidAccountId) {
+        self.registered_accounts.insert(account_id.as_ref());
+    }
+
+    pub fn unregister(&mut self, account_id: ValidAccountId) {
+        self.registered_accounts.remove(account_id.as_ref());
+    }
+
+    pub fn is_registered(&self, account_id: ValidAccountId) -> bool {
+        self.registered_accounts.contains(account_id.as_ref())
+    }
+}
@@ -0,0 +1,165 @@
+use std::cmp::min;
+
+use crate::*;
+
+const DEFAULT_ACTIVE_QUIZZES_LIMIT: u64 = 20;
+const MAX_ACTIVE_QUIZZES_LIMIT: u64 = 100;
+const MAX_ACTIVE_QUIZZES_SCAN: usize = 200;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActiveQuizOutput {
+    id: QuizId,
+    title: Option<String>,
+    language: Option<String>,
+    tags: Vec<String>,
+    finality_type: QuizFinalityType,
+    owner_id: AccountId,
+    total_questions: u16,
+    remaining_reward: WrappedBalance,
+    available_rewards_quantity: u16,
+    token_account_id: TokenAccountId,
+    restart_allowed: bool,
+    starts_at: Option<Timestamp>,
+    ends_at: Option<Timestamp>,
+    timestamp: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActiveQuizzesPage {
+    quizzes: Vec<ActiveQuizOutput>,
+    next_index: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActiveQuizzesFilter {
+    finality_type: Option<QuizFinalityType>,
+    token_account_id: Option<TokenAccountId>,
+    min_remaining_reward: Option<WrappedBalance>,
+    restart_allowed: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ActiveQuizzesSort {
+    Newest,
+    LargestPrize,
+    EndingSoonest
+}
+
+#[near_bindgen]
+impl QuizChain {
+    // Prizes in different tokens are compared by raw amounts, use the token filter to compare them.
+    // from_index is a position in the sorted index, the page is filled with up to limit matched quizzes
+    // scanning at most 200 positions. Next page starts from next_index, which is None after the last quiz
+    pub fn get_active_quizzes(&self, from_index: Option<u64>, limit: Option<u64>,
+                              filter: Option<ActiveQuizzesFilter>, sort: Option<ActiveQuizzesSort>) -> ActiveQuizzesPage {
+        let from_index = from_index.unwrap_or(0);
+        let limit = min(limit.unwrap_or(DEFAULT_ACTIVE_QUIZZES_LIMIT), MAX_ACTIVE_QUIZZES_LIMIT) as usize;
+
+        let quiz_ids: Box<dyn Iterator<Item = QuizId>> = match sort.unwrap_or(ActiveQuizzesSort::Newest) {
+            ActiveQuizzesSort::Newest => Box::new(self.active_quizzes_by_id.iter_rev().map(|(quiz_id, _)| quiz_id)),
+            ActiveQuizzesSort::LargestPrize => Box::new(self.active_quizzes_by_prize.iter_rev().map(|(_, quiz_id)| quiz_id)),
+            ActiveQuizzesSort::EndingSoonest => Box::new(self.active_quizzes_by_end.iter_from((env::block_timestamp(), QuizId::MAX))
+                .map(|(_, quiz_id)| quiz_id)),
+        };
+        let mut quiz_ids = quiz_ids.skip(from_index as usize).peekable();
+
+        let mut quizzes = Vec::new();
+        let mut next_index = from_index;
+        while quizzes.len() < limit && next_index - from_index < MAX_ACTIVE_QUIZZES_SCAN as u64 {
+            if let Some(quiz_id) = quiz_ids.next() {
+                next_index += 1;
+                if let Some(quiz) = self.get_active_quiz_output(quiz_id) {
+                    if QuizChain::is_active_quiz_matched(&quiz, &filter) {
+                        quizzes.push(quiz);
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+
+        ActiveQuizzesPage {
+            quizzes,
+            next_index: quiz_ids.peek().map(|_| next_index),
+        }
+    }
+
+    pub fn get_active_quizzes_count(&self) -> u64 {
+        self.active_quizzes.len()
+    }
+
+    fn get_active_quiz_output(&self, quiz_id: QuizId) -> Option<ActiveQuizOutput> {
+        self.quizzes.get(&quiz_id).map(|quiz| ActiveQuizOutput {
+            id: quiz_id,
+            remaining_reward: self.get_active_quiz_prize(quiz_id, &quiz).into(),
+            available_rewards_quantity: quiz.available_rewards_ids.len() as u16,
+            token_account_id: QuizChain::unwrap_token_id(&quiz.token_account_id),
+            title: quiz.title,
+            language: quiz.language,
+            tags: quiz.tags,
+            finality_type: quiz.finality_type,
+            owner_id: quiz.owner_id,
+            total_questions: quiz.total_questions,
+            restart_allowed: quiz.restart_allowed,
+            starts_at: quiz.starts_at,
+            ends_at: quiz.ends_at,
+            timestamp: quiz.timestamp,
+        })
+    }
+
+    fn is_active_quiz_matched(quiz: &ActiveQuizOutput, filter: &Option<ActiveQuizzesFilter>) -> bool {
+        if let Some(filter) = filter {
+            filter.finality_type.map(|finality_type| finality_type == quiz.finality_type).unwrap_or(true) &&
+                filter.token_account_id.as_ref().map(|token_account_id| *token_account_id == quiz.token_account_id).unwrap_or(true) &&
+                filter.min_remaining_reward.map(|min_remaining_reward| quiz.remaining_reward.0 >= min_remaining_reward.0).unwrap_or(true) &&
+                filter.restart_allowed.map(|restart_allowed| restart_allowed == quiz.restart_allowed).unwrap_or(true)
+        } else {
+            true
+        }
+    }
+
+    pub(crate) fn get_remaining_reward(&self, quiz_id: QuizId, quiz: &Quiz) -> Balance {
+        quiz.available_rewards_ids.iter()
+            .filter_map(|reward_id| self.rewards.get(&QuizChain::get_reward_by_quiz(quiz_id, *reward_id)))
+            .map(|reward| reward.amount)
+            .sum()
+    }
+
+    // Prize pool of entry fees is added to the next won reward
+    fn get_active_quiz_prize(&self, quiz_id: QuizId, quiz: &Quiz) -> Balance {
+        self.get_remaining_reward(quiz_id, quiz) + quiz.entry_fees_prize_pool
+    }
+
+    // Active quizzes are kept in sorted indexes for every sort order of get_active_quizzes
+    pub(crate) fn internal_add_active_quiz(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        let remaining_reward = self.get_active_quiz_prize(quiz_id, quiz);
+
+        self.active_quizzes.insert(&quiz_id);
+        self.active_quizzes_by_id.insert(&quiz_id, &quiz_id);
+        self.active_quizzes_by_prize.insert(&(remaining_reward, quiz_id), &quiz_id);
+        self.active_quizzes_prizes.insert(&quiz_id, &remaining_reward);
+        self.active_quizzes_by_end.insert(&(quiz.ends_at.unwrap_or(Timestamp::MAX), quiz_id), &quiz_id);
+    }
+
+    pub(crate) fn internal_remove_active_quiz(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        self.active_quizzes.remove(&quiz_id);
+        self.active_quizzes_by_id.remove(&quiz_id);
+        if let Some(remaining_reward) = self.active_quizzes_prizes.remove(&quiz_id) {
+            self.active_quizzes_by_prize.remove(&(remaining_reward, quiz_id));
+        }
+        self.active_quizzes_by_end.remove(&(quiz.ends_at.unwrap_or(Timestamp::MAX), quiz_id));
+    }
+
+    pub(crate) fn internal_update_active_quiz_prize(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        if let Some(previous_remaining_reward) = self.active_quizzes_prizes.get(&quiz_id) {
+            let remaining_reward = self.get_active_quiz_prize(quiz_id, quiz);
+            self.active_quizzes_by_prize.remove(&(previous_remaining_reward, quiz_id));
+            self.active_quizzes_by_prize.insert(&(remaining_reward, quiz_id), &quiz_id);
+            self.active_quizzes_prizes.insert(&quiz_id, &remaining_reward);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::cmp::min;
+use near_sdk::json_types::ValidAccountId;
+
+use crate::*;
+
+const MAX_ALLOWLIST_BATCH: usize = 100;
+
+#[near_bindgen]
+impl QuizChain {
+    // Quizzes with allowlist_only may be played only by accounts from the allowlist of the quiz
+    pub fn set_allowlist_only(&mut self, quiz_id: QuizId, allowlist_only: bool) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        quiz.allowlist_only = allowlist_only;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn add_to_allowlist(&mut self, quiz_id: QuizId, account_ids: Vec<ValidAccountId>) {
+        let mut allowlist = self.get_allowlist_for_owner(quiz_id, &account_ids);
+        for account_id in account_ids {
+            allowlist.insert(account_id.as_ref());
+        }
+        self.allowlists.insert(&quiz_id, &allowlist);
+    }
+
+    pub fn remove_from_allowlist(&mut self, quiz_id: QuizId, account_ids: Vec<ValidAccountId>) {
+        let mut allowlist = self.get_allowlist_for_owner(quiz_id, &account_ids);
+        for account_id in account_ids {
+            allowlist.remove(account_id.as_ref());
+        }
+        self.allowlists.insert(&quiz_id, &allowlist);
+    }
+
+    pub fn get_allowlist(&self, quiz_id: QuizId, from_index: usize, limit: usize) -> Vec<AccountId> {
+        let mut account_ids: Vec<AccountId> = Vec::new();
+        if let Some(allowlist) = self.allowlists.get(&quiz_id) {
+            let allowlist_qty = allowlist.len() as usize;
+            assert!(from_index <= allowlist_qty, "Illegal from_index");
+            let limit_id = min(from_index + limit, allowlist_qty);
+            let allowlist = allowlist.as_vector();
+            for account_index in from_index..limit_id {
+                if let Some(account_id) = allowlist.get(account_index as u64) {
+                    account_ids.push(account_id);
+                }
+            }
+        }
+        account_ids
+    }
+
+    pub fn get_allowlist_size(&self, quiz_id: QuizId) -> u64 {
+        self.allowlists.get(&quiz_id).map(|allowlist| allowlist.len()).unwrap_or(0)
+    }
+
+    pub fn is_allowlisted(&self, quiz_id: QuizId, account_id: ValidAccountId) -> bool {
+        self.allowlists.get(&quiz_id).map(|allowlist| allowlist.contains(account_id.as_ref())).unwrap_or(false)
+    }
+
+    pub(crate) fn assert_allowlisted(&self, quiz_id: QuizId, quiz: &Quiz, account_id: &AccountId) {
+        if quiz.allowlist_only {
+            assert!(self.allowlists.get(&quiz_id).map(|allowlist| allowlist.contains(account_id)).unwrap_or(false),
+                    "Account is not in the allowlist of the quiz");
+        }
+    }
+
+    fn get_allowlist_for_owner(&self, quiz_id: QuizId, account_ids: &[ValidAccountId]) -> UnorderedSet<AccountId> {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            QuizChain::assert_current_user(&quiz.owner_id);
+            assert!(account_ids.len() <= MAX_ALLOWLIST_BATCH, "Too many accounts in the batch");
+            self.allowlists.get(&quiz_id).unwrap_or_else(|| UnorderedSet::new(StorageKey::AllowlistsByQuiz { quiz_id }))
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::*;
+
+const DAYS_TO_REVEAL_ANSWERS: u64 = 1;
+
+#[near_bindgen]
+impl QuizChain {
+    // hash = sha256(account_id + answers_hash + nonce), where answers_hash is the hash chain of all
+    // answers calculated the same way as in send_answer
+    pub fn commit_answers(&mut self, quiz_id: QuizId, hash: Hash) {
+        assert_eq!(hash.chars().count(), 64, "Illegal hash length");
+        let account_id = env::predecessor_account_id();
+
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            assert_eq!(quiz.finality_type, QuizFinalityType::CommitReveal, "Commitments are not supported");
+            assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not active");
+            assert!(quiz.reveal_started_at.is_none(), "Commitments are closed");
+            QuizChain::assert_quiz_is_open(&quiz);
+
+            let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+            assert!(self.games.get(&game_id).is_some(), "Game wasn't started");
+
+            // a new commitment replaces the previous one, but the player keeps the place of the first commitment
+            let timestamp = if let Some(commitment) = self.answer_commitments.get(&game_id) {
+                commitment.timestamp
+            } else {
+                self.internal_add_finished_player(quiz_id, account_id);
+                env::block_timestamp()
+            };
+            self.answer_commitments.insert(&game_id,
+                                           &AnswerCommitment {
+                                               hash,
+                                               timestamp,
+                                               revealed: false,
+                                           });
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    pub fn reveal_committed_answers(&mut self, quiz_id: QuizId, answers: Vec<RevealedAnswer>, nonce: String) -> bool {
+        let account_id = env::predecessor_account_id();
+
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            assert_eq!(quiz.finality_type, QuizFinalityType::CommitReveal, "Commitments are not supported");
+            assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not active");
+            assert_eq!(quiz.total_questions, answers.len() as u16, "Illegal answers quantity");
+            if let Some(reveal_started_at) = quiz.reveal_started_at {
+                assert!(env::block_timestamp() <= QuizChain::get_answers_reveal_end(reveal_started_at), "Reveal period is over");
+            } else {
+                panic!("Reveal period wasn't started");
+            }
+
+            let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+            if let Some(mut commitment) = self.answer_commitments.get(&game_id) {
+                assert!(!commitment.revealed, "Answers were already revealed");
+
+                // players commit plain answers, accepted answers are matched with the revealed salt
+                let secret = quiz.secret.clone().unwrap();
+                let committed_answers_hash = self.get_revealed_answers_hash(quiz_id, secret.clone(), None, &answers);
+                assert_eq!(QuizChain::get_hash(format!("{}{}{}", account_id, committed_answers_hash, nonce)), commitment.hash,
+                           "Answers don't match the commitment");
+                let answers_hash = self.get_revealed_answers_hash(quiz_id, secret, quiz.salt.as_deref(), &answers);
+
+                for (question_id, answer) in answers.iter().enumerate() {
+                    let question = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id as QuestionId)).expect("Question not found");
+                    let selected_option_ids = answer.selected_option_ids.clone()
+                        .map(|option_ids| QuizChain::get_canonical_option_ids(&question, option_ids));
+                    self.answers.insert(&QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id as QuestionId, account_id.clone()),
+                                        &Answer {
+                                            selected_option_ids,
+                                            selected_text: answer.selected_text.as_ref()
+                                                .map(|text| QuizChain::get_text_answer(None, &question, text).0),
+                                            selected_pairs: answer.selected_pairs.clone()
+                                                .map(|pairs| self.get_matching_answer(quiz_id, question_id as QuestionId, &question, pairs).0),
+                                            timestamp: commitment.timestamp,
+                                        });
+                }
+
+                let mut game = self.games.get(&game_id).expect("Game wasn't started");
+                game.answers_quantity = quiz.total_questions;
+                game.current_hash = answers_hash.clone();
+                game.question_started_at = env::block_timestamp();
+                self.games.insert(&game_id, &game);
+
+                commitment.revealed = true;
+                self.answer_commitments.insert(&game_id, &commitment);
+
+                let is_correct = Some(answers_hash.clone()) == quiz.success_hash;
+                if is_correct {
+                    self.stop_game(answers_hash, &quiz_id);
+                }
+                is_correct
+            } else {
+                panic!("Commitment not found");
+            }
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    // Players with valid revealed answers in order of their commitments
+    pub(crate) fn get_commit_reveal_winners(&self, quiz_id: QuizId, quiz: &Quiz) -> Vec<AccountId> {
+        let mut winners: Vec<AccountId> = self.quiz_results.get(
+            &QuizResultByQuiz { quiz_id, hash: quiz.success_hash.clone().unwrap() }).unwrap_or([].to_vec());
+        winners.sort_by_cached_key(|account_id|
+            self.answer_commitments.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.clone())).unwrap().timestamp);
+        winners
+    }
+
+    pub(crate) fn get_answers_reveal_end(reveal_started_at: Timestamp) -> Timestamp {
+        reveal_started_at + DAY_IN_NANOSECONDS * DAYS_TO_REVEAL_ANSWERS
+    }
+
+    pub fn get_answer_commitment(&self, quiz_id: QuizId, account_id: ValidAccountId) -> Option<AnswerCommitment> {
+        self.answer_commitments.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.into()))
+    }
+}
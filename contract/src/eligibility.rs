@@ -0,0 +1,159 @@
+use near_sdk::{Gas, PromiseResult};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::serde_json::json;
+
+use crate::*;
+
+const GAS_FOR_ELIGIBILITY_GATE: Gas = 10_000_000_000_000;
+const GAS_FOR_ON_ELIGIBILITY_CHECKED: Gas = 40_000_000_000_000;
+const NO_DEPOSIT: Balance = 0;
+const MAX_ELIGIBILITY_GATES: usize = 5;
+
+#[ext_contract(ext_self)]
+pub trait ExtQuizChain {
+    fn on_eligibility_checked(&mut self, quiz_id: QuizId, account_id: AccountId, referrer_id: Option<ValidAccountId>,
+                              entry_fee: WrappedBalance) -> WrappedBalance;
+}
+
+#[near_bindgen]
+impl QuizChain {
+    // Players have to pass all gates of the quiz to start the game
+    pub fn set_eligibility_gates(&mut self, quiz_id: QuizId, eligibility_gates: Vec<EligibilityGate>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(eligibility_gates.len() <= MAX_ELIGIBILITY_GATES, "Too many eligibility gates");
+        for eligibility_gate in &eligibility_gates {
+            match eligibility_gate {
+                EligibilityGate::FtBalance { token_account_id, .. } =>
+                    self.assert_check_whitelisted_token(&Some(token_account_id.clone())),
+                EligibilityGate::NftOwnership { nft_account_id } =>
+                    assert!(env::is_valid_account_id(nft_account_id.as_bytes()), "Illegal account id"),
+                EligibilityGate::Registry { registry_account_id, method_name } => {
+                    assert!(env::is_valid_account_id(registry_account_id.as_bytes()), "Illegal account id");
+                    assert!(!method_name.is_empty(), "Method name is missing");
+                }
+            }
+        }
+
+        quiz.eligibility_gates = eligibility_gates;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Games of quizzes with eligibility gates are created in on_eligibility_checked, so start_game
+    // needs enough gas for a call of every gate and the callback
+    pub(crate) fn internal_request_game(&mut self, quiz_id: QuizId, account_id: AccountId, referrer_id: Option<ValidAccountId>,
+                                        entry_fee: Balance, token_account_id: Option<TokenAccountId>) -> PromiseOrValue<WrappedBalance> {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            self.assert_allowlisted(quiz_id, &quiz, &account_id);
+            if quiz.eligibility_gates.is_empty() {
+                self.internal_start_game(quiz_id, account_id, referrer_id, entry_fee, token_account_id);
+                return PromiseOrValue::Value(0.into());
+            }
+
+            // entry fee can't be refunded if the callback fails, so the game is checked before the gates
+            QuizChain::assert_game_available_to_play(&quiz.status);
+            QuizChain::assert_quiz_is_open(&quiz);
+            assert!(self.games.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.clone())).is_none(), "Game already in progress");
+            self.assert_player_can_join(quiz_id, &quiz, &account_id);
+            QuizChain::assert_valid_entry_fee(&quiz, entry_fee, &token_account_id);
+
+            let mut promise: Option<Promise> = None;
+            for eligibility_gate in &quiz.eligibility_gates {
+                let gate_promise = QuizChain::get_eligibility_gate_promise(eligibility_gate, &account_id);
+                promise = Some(match promise {
+                    Some(promise) => promise.and(gate_promise),
+                    None => gate_promise
+                });
+            }
+
+            PromiseOrValue::Promise(promise.unwrap().then(ext_self::on_eligibility_checked(
+                quiz_id,
+                account_id,
+                referrer_id,
+                entry_fee.into(),
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_ON_ELIGIBILITY_CHECKED,
+            )))
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    // Returns the unused amount of fungible tokens, entry fees in NEAR are refunded here.
+    // Entry fees were already checked to be paid in the token of the quiz, the quiz may change
+    // while the gates are checked, so the entry fee is refunded as well if the game can't be started
+    #[private]
+    pub fn on_eligibility_checked(&mut self, quiz_id: QuizId, account_id: AccountId, referrer_id: Option<ValidAccountId>,
+                                  entry_fee: WrappedBalance) -> WrappedBalance {
+        let quiz = self.quizzes.get(&quiz_id).expect("Quiz not found");
+        let token_account_id = if entry_fee.0 > 0 { quiz.token_account_id.clone() } else { None };
+        assert_eq!(env::promise_results_count(), quiz.eligibility_gates.len() as u64, "Contract expected a result of every gate");
+
+        let is_eligible = quiz.eligibility_gates.iter().enumerate()
+            .all(|(index, eligibility_gate)| QuizChain::is_eligibility_gate_passed(eligibility_gate, env::promise_result(index as u64)));
+
+        if is_eligible && self.can_start_game(quiz_id, &quiz, &account_id, entry_fee.0) {
+            self.internal_start_game(quiz_id, account_id, referrer_id, entry_fee.0, token_account_id);
+            0.into()
+        } else {
+            if is_eligible {
+                log!("Account {} can't start the game of quiz {} anymore", account_id, quiz_id);
+            } else {
+                log!("Account {} is not eligible to play quiz {}", account_id, quiz_id);
+            }
+            if token_account_id.is_none() && entry_fee.0 > 0 {
+                Promise::new(account_id).transfer(entry_fee.0);
+                0.into()
+            } else {
+                entry_fee
+            }
+        }
+    }
+
+    // Same checks as in internal_start_game, which can't panic after the entry fee was paid.
+    // Entry fee is accepted only while the quiz is in progress, otherwise it is refunded
+    fn can_start_game(&self, quiz_id: QuizId, quiz: &Quiz, account_id: &AccountId, entry_fee: Balance) -> bool {
+        let now = env::block_timestamp();
+        let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+        [QuizStatus::InProgress, QuizStatus::Finished, QuizStatus::Expired].contains(&quiz.status) &&
+            (entry_fee == 0 || quiz.status == QuizStatus::InProgress) &&
+            quiz.starts_at.map(|starts_at| now >= starts_at).unwrap_or(true) &&
+            quiz.ends_at.map(|ends_at| now <= ends_at).unwrap_or(true) &&
+            quiz.reveal_started_at.is_none() &&
+            self.games.get(&game_id).is_none() &&
+            !self.released_players.contains(&game_id) &&
+            !self.waitlisted_accounts.contains(&game_id) &&
+            (quiz.waitlist_enabled || self.has_free_player_place(quiz_id, quiz))
+    }
+
+    fn get_eligibility_gate_promise(eligibility_gate: &EligibilityGate, account_id: &AccountId) -> Promise {
+        let (contract_id, method_name) = match eligibility_gate {
+            EligibilityGate::FtBalance { token_account_id, .. } => (token_account_id.clone(), "ft_balance_of".to_string()),
+            // NEP-181 enumeration of tokens from the NEP-171 contract
+            EligibilityGate::NftOwnership { nft_account_id } => (nft_account_id.clone(), "nft_supply_for_owner".to_string()),
+            EligibilityGate::Registry { registry_account_id, method_name } => (registry_account_id.clone(), method_name.clone()),
+        };
+
+        Promise::new(contract_id).function_call(
+            method_name.into_bytes(),
+            json!({ "account_id": account_id }).to_string().into_bytes(),
+            NO_DEPOSIT,
+            GAS_FOR_ELIGIBILITY_GATE,
+        )
+    }
+
+    fn is_eligibility_gate_passed(eligibility_gate: &EligibilityGate, promise_result: PromiseResult) -> bool {
+        if let PromiseResult::Successful(value) = promise_result {
+            match eligibility_gate {
+                EligibilityGate::FtBalance { min_balance, .. } =>
+                    near_sdk::serde_json::from_slice::<WrappedBalance>(&value).map(|balance| balance.0 >= min_balance.0).unwrap_or(false),
+                EligibilityGate::NftOwnership { .. } =>
+                    near_sdk::serde_json::from_slice::<WrappedBalance>(&value).map(|supply| supply.0 > 0).unwrap_or(false),
+                EligibilityGate::Registry { .. } =>
+                    near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(false),
+            }
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use near_sdk::json_types::ValidAccountId;
+
+use crate::*;
+
+const ENTRY_FEE_RATE_DENOMINATOR: u16 = 10000;
+// 1%, the same as the service fee of new quizzes
+const MIN_ENTRY_FEE_SERVICE_RATE: u16 = 100;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EntryFeeInput {
+    amount: WrappedBalance,
+    prize_pool_rate: u16,
+    owner_rate: u16,
+    service_rate: u16,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EntryFeeOutput {
+    amount: WrappedBalance,
+    prize_pool_rate: u16,
+    owner_rate: u16,
+    service_rate: u16,
+    entry_fees_quantity: u64,
+    prize_pool: WrappedBalance,
+    withdrawn: bool,
+}
+
+#[near_bindgen]
+impl QuizChain {
+    // Rates are set in basis points. The prize pool part of every entry fee is added to the next won reward
+    pub fn set_entry_fee(&mut self, quiz_id: QuizId, entry_fee: Option<EntryFeeInput>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+
+        if let Some(entry_fee) = entry_fee.as_ref() {
+            assert!(entry_fee.amount.0 > 0, "Illegal entry fee");
+            assert!(!quiz.waitlist_enabled, "Entry fee is not available for quizzes with waitlist");
+            assert_eq!(entry_fee.prize_pool_rate as u32 + entry_fee.owner_rate as u32 + entry_fee.service_rate as u32,
+                       ENTRY_FEE_RATE_DENOMINATOR as u32, "Entry fee rates should sum up to {}", ENTRY_FEE_RATE_DENOMINATOR);
+            assert!(entry_fee.service_rate >= MIN_ENTRY_FEE_SERVICE_RATE, "Service rate is too low");
+        }
+
+        quiz.entry_fee = entry_fee.map(|entry_fee| EntryFee {
+            amount: entry_fee.amount.0,
+            prize_pool_rate: entry_fee.prize_pool_rate,
+            owner_rate: entry_fee.owner_rate,
+            service_rate: entry_fee.service_rate,
+        });
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn get_entry_fee_payment(&self, quiz_id: QuizId, account_id: ValidAccountId) -> Option<WrappedBalance> {
+        self.entry_fee_payments.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.into())).map(|amount| amount.into())
+    }
+
+    // Owner and service parts of entry fees are kept until the end of the quiz to refund players of cancelled quizzes
+    pub fn withdraw_entry_fees(&mut self, quiz_id: QuizId) -> PromiseOrValue<bool> {
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+            QuizChain::assert_current_user(&quiz.owner_id);
+            assert!([QuizStatus::Finished, QuizStatus::Expired].contains(&quiz.status), "Quiz is not finished");
+            assert!(!quiz.entry_fees_withdrawn, "Entry fees were already withdrawn");
+
+            if let Some(entry_fee) = quiz.entry_fee.as_ref() {
+                let (_, owner_share, service_share) = QuizChain::get_entry_fee_shares(entry_fee);
+                // prize pool left without winners goes to the owner
+                let owner_amount = owner_share * quiz.entry_fees_quantity as Balance + quiz.entry_fees_prize_pool;
+                let service_amount = service_share * quiz.entry_fees_quantity as Balance;
+
+                quiz.entry_fees_prize_pool = 0;
+                quiz.entry_fees_withdrawn = true;
+                self.quizzes.insert(&quiz_id, &quiz);
+
+                self.add_service_fees_total(service_amount, &quiz.token_account_id);
+                if owner_amount > 0 {
+                    return PromiseOrValue::Promise(self.withdraw(quiz.owner_id, owner_amount, quiz.token_account_id, None, None));
+                }
+            }
+            PromiseOrValue::Value(true)
+        } else {
+            PromiseOrValue::Value(false)
+        }
+    }
+
+    // Every payment of a cancelled quiz is refunded with an equal part of entry fees which weren't won by players
+    pub fn claim_entry_fee_refund(&mut self, quiz_id: QuizId) -> PromiseOrValue<bool> {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            assert_eq!(quiz.status, QuizStatus::Cancelled, "Quiz is not cancelled");
+            let account_id = env::predecessor_account_id();
+            let payment_index = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+
+            if let (Some(entry_fee), Some(paid_amount)) = (quiz.entry_fee.as_ref(), self.entry_fee_payments.get(&payment_index)) {
+                let (_, owner_share, service_share) = QuizChain::get_entry_fee_shares(entry_fee);
+                let refundable_amount = quiz.entry_fees_prize_pool + (owner_share + service_share) * quiz.entry_fees_quantity as Balance;
+                let refund = refundable_amount * (paid_amount / entry_fee.amount) / quiz.entry_fees_quantity as Balance;
+
+                self.entry_fee_payments.remove(&payment_index);
+                if refund > 0 {
+                    return PromiseOrValue::Promise(self.withdraw(account_id, refund, quiz.token_account_id, None, None));
+                }
+                return PromiseOrValue::Value(true);
+            }
+        }
+        PromiseOrValue::Value(false)
+    }
+
+    // Entry fees are paid only while the quiz is in progress, finished quizzes are played for free
+    pub(crate) fn internal_pay_entry_fee(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId, amount: Balance,
+                                         token_account_id: &Option<TokenAccountId>) {
+        QuizChain::assert_valid_entry_fee(quiz, amount, token_account_id);
+        if let Some(entry_fee) = quiz.entry_fee.as_ref() {
+            if quiz.status == QuizStatus::InProgress {
+                let (prize_pool_share, _, _) = QuizChain::get_entry_fee_shares(entry_fee);
+                quiz.entry_fees_quantity += 1;
+                quiz.entry_fees_prize_pool += prize_pool_share;
+                self.quizzes.insert(&quiz_id, quiz);
+                self.internal_update_active_quiz_prize(quiz_id, quiz);
+
+                let payment_index = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+                let paid_amount = self.entry_fee_payments.get(&payment_index).unwrap_or(0);
+                self.entry_fee_payments.insert(&payment_index, &(paid_amount + amount));
+            }
+        }
+    }
+
+    pub(crate) fn assert_valid_entry_fee(quiz: &Quiz, amount: Balance, token_account_id: &Option<TokenAccountId>) {
+        match quiz.entry_fee.as_ref() {
+            Some(entry_fee) if quiz.status == QuizStatus::InProgress => {
+                assert_eq!(*token_account_id, quiz.token_account_id, "Entry fee is paid in the token of the quiz");
+                assert_eq!(amount, entry_fee.amount, "Entry fee of the quiz is {}", entry_fee.amount);
+            }
+            _ => assert_eq!(amount, 0, "Quiz has no entry fee")
+        }
+    }
+
+    // Moves the prize pool of entry fees to the reward
+    pub(crate) fn internal_add_entry_fees_to_reward(quiz: &mut Quiz, reward: &mut Reward) {
+        reward.amount += quiz.entry_fees_prize_pool;
+        quiz.entry_fees_prize_pool = 0;
+    }
+
+    // Returns prize pool, owner and service parts of the entry fee, rounding leftovers go to the service
+    pub(crate) fn get_entry_fee_shares(entry_fee: &EntryFee) -> (Balance, Balance, Balance) {
+        let prize_pool_share = entry_fee.amount * entry_fee.prize_pool_rate as Balance / ENTRY_FEE_RATE_DENOMINATOR as Balance;
+        let owner_share = entry_fee.amount * entry_fee.owner_rate as Balance / ENTRY_FEE_RATE_DENOMINATOR as Balance;
+        (prize_pool_share, owner_share, entry_fee.amount - prize_pool_share - owner_share)
+    }
+
+    pub(crate) fn get_entry_fee_output(quiz: &Quiz) -> Option<EntryFeeOutput> {
+        quiz.entry_fee.as_ref().map(|entry_fee| EntryFeeOutput {
+            amount: entry_fee.amount.into(),
+            prize_pool_rate: entry_fee.prize_pool_rate,
+            owner_rate: entry_fee.owner_rate,
+            service_rate: entry_fee.service_rate,
+            entry_fees_quantity: quiz.entry_fees_quantity,
+            prize_pool: quiz.entry_fees_prize_pool.into(),
+            withdrawn: quiz.entry_fees_withdrawn,
+        })
+    }
+}
@@ -1,10 +1,11 @@
 use crate::*;
+use crate::invitation::Invitation;
 
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TransferArgs {
     pub operation: String,
-    pub quiz_owner_id: ValidAccountId,
+    pub quiz_owner_id: Option<ValidAccountId>,
     pub title: Option<String>,
     pub description: Option<String>,
     pub language: Option<String>,
@@ -14,16 +15,23 @@ pub struct TransferArgs {
     pub rewards: Option<Vec<RewardInput>>,
     pub secret: Option<String>,
     pub success_hash: Option<String>,
+    pub success_hash_commitment: Option<String>,
     pub restart_allowed: Option<bool>,
+    pub starts_at: Option<Timestamp>,
+    pub ends_at: Option<Timestamp>,
+    pub source_quiz_id: Option<QuizId>,
+    pub quiz_id: Option<QuizId>,
+    pub referrer_id: Option<ValidAccountId>,
+    pub invitation: Option<Invitation>,
 }
 
 trait FungibleTokenReceiver {
-    fn ft_on_transfer(&mut self, sender_id: ValidAccountId, amount: WrappedBalance, msg: String) -> PromiseOrValue<QuizId>;
+    fn ft_on_transfer(&mut self, sender_id: ValidAccountId, amount: WrappedBalance, msg: String) -> PromiseOrValue<WrappedBalance>;
 }
 
 #[near_bindgen]
 impl FungibleTokenReceiver for QuizChain {
-    fn ft_on_transfer(&mut self, sender_id: ValidAccountId, amount: WrappedBalance, msg: String) -> PromiseOrValue<QuizId> {
+    fn ft_on_transfer(&mut self, sender_id: ValidAccountId, amount: WrappedBalance, msg: String) -> PromiseOrValue<WrappedBalance> {
         let token_account_id: Option<TokenAccountId> = Some(env::predecessor_account_id());
         self.assert_check_whitelisted_token(&token_account_id);
 
@@ -39,10 +47,24 @@ impl FungibleTokenReceiver for QuizChain {
             rewards,
             secret,
             success_hash,
-            restart_allowed
+            success_hash_commitment,
+            restart_allowed,
+            starts_at,
+            ends_at,
+            source_quiz_id,
+            quiz_id,
+            referrer_id,
+            invitation
         } = near_sdk::serde_json::from_str(&msg).expect("Invalid TransferArgs");
 
-        let quiz_owner_value: AccountId = quiz_owner_id.into();
+        if operation == "start_game" {
+            let quiz_id = quiz_id.expect("Quiz id is missing");
+            let account_id: AccountId = sender_id.into();
+            self.assert_invitation(quiz_id, &account_id, invitation);
+            return self.internal_request_game(quiz_id, account_id, referrer_id, amount.0, token_account_id);
+        }
+
+        let quiz_owner_value: AccountId = quiz_owner_id.expect("Quiz owner is missing").into();
 
         let sale_id =
         if operation == "create_quiz_for_account" {
@@ -65,7 +87,10 @@ impl FungibleTokenReceiver for QuizChain {
                                           rewards.unwrap(),
                                           secret_unwrapped,
                                           success_hash.clone(),
+                                          success_hash_commitment.clone(),
                                           restart_allowed.unwrap(),
+                                          starts_at,
+                                          ends_at,
                                           amount.0,
                                           token_account_id)
             }
@@ -80,16 +105,37 @@ impl FungibleTokenReceiver for QuizChain {
                                           rewards.unwrap(),
                                           secret.clone(),
                                           success_hash.clone(),
+                                          success_hash_commitment.clone(),
                                           restart_allowed.unwrap(),
+                                          starts_at,
+                                          ends_at,
                                           amount.0,
                                           token_account_id)
             }
+        } else if operation == "clone_quiz" {
+            self.clone_quiz_internal(quiz_owner_value.clone(),
+                                     sender_id.into(),
+                                     source_quiz_id.expect("Source quiz is missing"),
+                                     title,
+                                     description,
+                                     language,
+                                     finality_type.unwrap(),
+                                     rewards.unwrap(),
+                                     secret,
+                                     success_hash,
+                                     success_hash_commitment,
+                                     restart_allowed.unwrap(),
+                                     starts_at,
+                                     ends_at,
+                                     amount.0,
+                                     token_account_id)
         } else {
             panic!("Unknown operation");
         };
 
         log!("Quiz {} created", sale_id);
 
-        PromiseOrValue::Value(0)
+        // unused amount of tokens
+        PromiseOrValue::Value(0.into())
     }
 }
@@ -1,6 +1,9 @@
 use std::cmp::min;
 
 use crate::*;
+use crate::invitation::Invitation;
+
+const LATE_ANSWER: &str = "#late";
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -8,10 +11,19 @@ pub struct AnswerOutput {
     id: AnswerId,
     selected_option_ids: Option<Vec<QuestionOptionId>>,
     selected_text: Option<String>,
+    selected_pairs: Option<Vec<QuestionOptionPair>>,
     timestamp: Timestamp,
     is_correct: Option<bool>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnswerInput {
+    question_option_ids: Option<Vec<QuestionOptionId>>,
+    question_option_text: Option<String>,
+    question_option_pairs: Option<Vec<QuestionOptionPair>>,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct StatsOutput {
@@ -24,68 +36,215 @@ pub struct StatsOutput {
 impl QuizChain {
     pub fn send_answer(&mut self, quiz_id: QuizId, question_id: QuestionId,
                        question_option_ids: Option<Vec<QuestionOptionId>>,
-                       question_option_text: Option<String>) {
+                       question_option_text: Option<String>,
+                       question_option_pairs: Option<Vec<QuestionOptionPair>>) {
+        self.send_answers(quiz_id, question_id, vec![AnswerInput {
+            question_option_ids,
+            question_option_text,
+            question_option_pairs,
+        }]);
+    }
+
+    // Answers are applied to questions of the game in order starting from from_question_id,
+    // the same way as send_answer calls made one after another
+    pub fn send_answers(&mut self, quiz_id: QuizId, from_question_id: QuestionId, answers: Vec<AnswerInput>) {
+        assert!(!answers.is_empty(), "Answers are missing");
         let game_id = QuizChain::get_quiz_by_user(quiz_id, env::predecessor_account_id());
         if let Some(mut game) = self.games.get(&game_id) {
-            assert_eq!(question_id, game.answers_quantity, "Wrong index of the answer");
-
             if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+                assert_eq!(Some(from_question_id), QuizChain::get_game_question_id(&quiz, &game, game.answers_quantity), "Wrong index of the answer");
                 QuizChain::assert_game_available_to_play(&quiz.status);
+                assert_ne!(quiz.finality_type, QuizFinalityType::CommitReveal, "Answers of this quiz are sent with commit_answers");
+                QuizChain::assert_quiz_is_open(&quiz);
 
-                if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)) {
-                    let mut answer_to_hash: String = "".to_string();
-
-                    if question.kind == QuestionKind::Text {
-                        if let Some(question_option_text_unwrapped) = question_option_text.clone(){
-                            answer_to_hash = question_option_text_unwrapped.to_lowercase();
-                            self.answers.insert(&QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, env::predecessor_account_id()),
-                                                &Answer {
-                                                    selected_option_ids: None,
-                                                    selected_text: Some(answer_to_hash.clone()),
-                                                    timestamp: env::block_timestamp(),
-                                                });
-                        }
-                        else {
-                            panic!("Answer Text is missing");
-                        }
-                    } else { // OneChoice & MultipleChoice
-                        if let Some(mut question_option_ids_unwrapped) = question_option_ids{
-                            question_option_ids_unwrapped.sort();
-                            for question_option_id in &question_option_ids_unwrapped {
-                                if let Some(question_option) = self.question_options.get(
-                                    &QuizChain::get_question_option_by_quiz(quiz_id, question_id, *question_option_id)) {
-                                    answer_to_hash = format!("{}{}", answer_to_hash, question_option.content).to_lowercase();
-                                } else {
-                                    panic!("Question option not found");
-                                }
-                            }
-                            self.answers.insert(&QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, env::predecessor_account_id()),
-                                                &Answer {
-                                                    selected_option_ids: Some(question_option_ids_unwrapped),
-                                                    selected_text: None,
-                                                    timestamp: env::block_timestamp(),
-                                                });
-                        }
-                        else{
-                            panic!("Answer Options are missing")
-                        }
-                    }
+                for answer in answers {
+                    // players of question pools answer their own subset of questions
+                    let question_id = QuizChain::get_game_question_id(&quiz, &game, game.answers_quantity).expect("Wrong index of the answer");
+                    self.internal_send_answer(quiz_id, &quiz, &mut game, question_id, answer);
+                }
 
-                    game.answers_quantity += 1;
-                    let concat_hash = format!("{}{}", game.current_hash, answer_to_hash.clone());
-                    let new_hash = QuizChain::get_hash(concat_hash);
-                    game.current_hash = new_hash.clone();
-                    self.games.insert(&game_id, &game);
-                    //log!("Answer '{}' added. New game hash: {}", answer_to_hash, new_hash);
-
-                    if quiz.status == QuizStatus::InProgress && game.answers_quantity == quiz.total_questions {
-                        match quiz.finality_type {
-                            QuizFinalityType::Direct => self.finalize_game(&game, &quiz_id, &mut quiz),
-                            QuizFinalityType::DelayedReveal => self.stop_game(game.current_hash, &quiz_id),
-                        };
+                let game_finished = !quiz.revisable_answers && game.answers_quantity == QuizChain::get_questions_per_game(&quiz);
+                if game_finished {
+                    game.submitted_at = Some(env::block_timestamp());
+                }
+                self.games.insert(&game_id, &game);
+
+                if quiz.status == QuizStatus::InProgress && game_finished {
+                    self.internal_finish_game(&game, quiz_id, &mut quiz);
+                }
+            } else {
+                panic!("Quiz not found");
+            }
+        } else {
+            panic!("Game wasn't started");
+        }
+    }
+
+    fn internal_send_answer(&mut self, quiz_id: QuizId, quiz: &Quiz, game: &mut Game, question_id: QuestionId, answer: AnswerInput) {
+        if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)) {
+            let (answer, answer_to_hash) = if QuizChain::is_answer_late(&question, game) {
+                // late answers are wrong whatever was sent
+                (Answer {
+                    selected_option_ids: None,
+                    selected_text: None,
+                    selected_pairs: None,
+                    timestamp: env::block_timestamp(),
+                }, LATE_ANSWER.to_string())
+            } else {
+                self.get_player_answer(quiz_id, quiz, game, question_id, &question, answer)
+            };
+            self.answers.insert(&QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, env::predecessor_account_id()), &answer);
+
+            if quiz.questions_per_game.is_some() && QuizChain::is_answer_correct(quiz, &question, &answer_to_hash) {
+                game.correct_answers += 1;
+            }
+
+            game.answers_quantity += 1;
+            let concat_hash = format!("{}{}", game.current_hash, answer_to_hash.clone());
+            let new_hash = QuizChain::get_hash(concat_hash);
+            game.current_hash = new_hash.clone();
+            game.question_started_at = env::block_timestamp();
+            //log!("Answer '{}' added. New game hash: {}", answer_to_hash, new_hash);
+        } else {
+            panic!("Question not found");
+        }
+    }
+
+    // Returns the answer stored for the player and the value used in the answers hash
+    fn get_player_answer(&self, quiz_id: QuizId, quiz: &Quiz, game: &Game, question_id: QuestionId, question: &Question,
+                         answer: AnswerInput) -> (Answer, String) {
+        if [QuestionKind::Text, QuestionKind::Numeric].contains(&question.kind) {
+            if let Some(question_option_text_unwrapped) = answer.question_option_text {
+                let (answer_text, answer_value) = QuizChain::get_text_answer(QuizChain::get_answer_salt(quiz), question, &question_option_text_unwrapped);
+                (Answer {
+                    selected_option_ids: None,
+                    selected_text: Some(answer_text),
+                    selected_pairs: None,
+                    timestamp: env::block_timestamp(),
+                }, answer_value)
+            }
+            else {
+                panic!("Answer Text is missing");
+            }
+        } else if question.kind == QuestionKind::Matching {
+            if let Some(question_option_pairs_unwrapped) = answer.question_option_pairs {
+                let question_option_pairs_unwrapped = match QuizChain::get_options_order(quiz, game, question_id, question.options_quantity) {
+                    Some(options_order) => question_option_pairs_unwrapped.iter()
+                        .map(|(left_option_id, right_option_id)| (
+                            *options_order.get(*left_option_id as usize).expect("Question option not found"),
+                            *options_order.get(*right_option_id as usize).expect("Question option not found")))
+                        .collect(),
+                    None => question_option_pairs_unwrapped
+                };
+                let (selected_pairs, answer_value) = self.get_matching_answer(quiz_id, question_id, question, question_option_pairs_unwrapped);
+                (Answer {
+                    selected_option_ids: None,
+                    selected_text: None,
+                    selected_pairs: Some(selected_pairs),
+                    timestamp: env::block_timestamp(),
+                }, answer_value)
+            }
+            else {
+                panic!("Answer Pairs are missing");
+            }
+        } else { // OneChoice, MultipleChoice & Ordering
+            if let Some(question_option_ids_unwrapped) = answer.question_option_ids {
+                // players of quizzes with shuffled options send ids of their own options view
+                let question_option_ids_unwrapped = match QuizChain::get_options_order(quiz, game, question_id, question.options_quantity) {
+                    Some(options_order) => question_option_ids_unwrapped.iter()
+                        .map(|question_option_id| *options_order.get(*question_option_id as usize).expect("Question option not found"))
+                        .collect(),
+                    None => question_option_ids_unwrapped
+                };
+                let question_option_ids_unwrapped = QuizChain::get_canonical_option_ids(question, question_option_ids_unwrapped);
+                let mut answer_to_hash: String = "".to_string();
+                for question_option_id in &question_option_ids_unwrapped {
+                    if let Some(question_option) = self.question_options.get(
+                        &QuizChain::get_question_option_by_quiz(quiz_id, question_id, *question_option_id)) {
+                        answer_to_hash = format!("{}{}", answer_to_hash, question_option.content).to_lowercase();
+                    } else {
+                        panic!("Question option not found");
                     }
-                } else {
-                    panic!("Question not found");
+                }
+                (Answer {
+                    selected_option_ids: Some(question_option_ids_unwrapped),
+                    selected_text: None,
+                    selected_pairs: None,
+                    timestamp: env::block_timestamp(),
+                }, answer_to_hash)
+            }
+            else{
+                panic!("Answer Options are missing")
+            }
+        }
+    }
+
+    fn is_answer_correct(quiz: &Quiz, question: &Question, answer_to_hash: &str) -> bool {
+        if let (Some(answer_hash), Some(salt)) = (question.answer_hash.as_ref(), QuizChain::get_answer_salt(quiz)) {
+            *answer_hash == QuizChain::get_answer_hash(salt, answer_to_hash)
+        } else {
+            false
+        }
+    }
+
+    // Games of quizzes with revisable answers are finished with submit_game
+    pub fn revise_answer(&mut self, quiz_id: QuizId, question_id: QuestionId,
+                         question_option_ids: Option<Vec<QuestionOptionId>>,
+                         question_option_text: Option<String>,
+                         question_option_pairs: Option<Vec<QuestionOptionPair>>) {
+        let game_id = QuizChain::get_quiz_by_user(quiz_id, env::predecessor_account_id());
+        if let Some(game) = self.games.get(&game_id) {
+            if let Some(quiz) = self.quizzes.get(&quiz_id) {
+                assert!(quiz.revisable_answers, "Answers of this quiz can't be changed");
+                assert!(game.submitted_at.is_none(), "Answers were already submitted");
+                QuizChain::assert_game_available_to_play(&quiz.status);
+                QuizChain::assert_quiz_is_open(&quiz);
+
+                let mut answered_question_ids = QuizChain::get_game_question_ids(&quiz, &game);
+                answered_question_ids.truncate(game.answers_quantity as usize);
+                assert!(answered_question_ids.contains(&question_id), "Question wasn't answered");
+
+                let answer_index = QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, env::predecessor_account_id());
+                let previous_answer = self.answers.get(&answer_index).expect("Answer not found");
+                assert!(!QuizChain::is_late_answer(&previous_answer), "Late answers can't be changed");
+
+                let question = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)).expect("Question not found");
+                assert!(question.time_limit.is_none(), "Answers of timed questions can't be changed");
+                let (answer, _) = self.get_player_answer(quiz_id, &quiz, &game, question_id, &question, AnswerInput {
+                    question_option_ids,
+                    question_option_text,
+                    question_option_pairs,
+                });
+                self.answers.insert(&answer_index, &answer);
+            } else {
+                panic!("Quiz not found");
+            }
+        } else {
+            panic!("Game wasn't started");
+        }
+    }
+
+    // The answers hash is built again from the stored answers, since they may have been changed
+    pub fn submit_game(&mut self, quiz_id: QuizId) {
+        let game_id = QuizChain::get_quiz_by_user(quiz_id, env::predecessor_account_id());
+        if let Some(mut game) = self.games.get(&game_id) {
+            if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+                assert!(quiz.revisable_answers, "Answers of this quiz are submitted with the last answer");
+                assert!(game.submitted_at.is_none(), "Answers were already submitted");
+                assert_eq!(game.answers_quantity, QuizChain::get_questions_per_game(&quiz), "Not all questions were answered");
+                QuizChain::assert_game_available_to_play(&quiz.status);
+                QuizChain::assert_quiz_is_open(&quiz);
+
+                let account_id = env::predecessor_account_id();
+                game.current_hash = self.get_stored_answers_hash(quiz_id, &quiz, &game, &account_id, QuizChain::get_answer_salt(&quiz));
+                if quiz.questions_per_game.is_some() {
+                    game.correct_answers = self.get_correct_answers(quiz_id, &quiz, &game, &account_id);
+                }
+                game.submitted_at = Some(env::block_timestamp());
+                self.games.insert(&game_id, &game);
+
+                if quiz.status == QuizStatus::InProgress {
+                    self.internal_finish_game(&game, quiz_id, &mut quiz);
                 }
             } else {
                 panic!("Quiz not found");
@@ -95,6 +254,76 @@ impl QuizChain {
         }
     }
 
+    fn get_stored_answers_hash(&self, quiz_id: QuizId, quiz: &Quiz, game: &Game, account_id: &AccountId, salt: Option<&str>) -> Hash {
+        let mut hash = QuizChain::get_hash(quiz.secret.clone().unwrap());
+        for question_id in QuizChain::get_game_question_ids(quiz, game) {
+            let answer_to_hash = self.get_stored_answer_value(quiz_id, question_id, account_id, salt);
+            hash = QuizChain::get_hash(format!("{}{}", hash, answer_to_hash));
+        }
+        hash
+    }
+
+    // Value of the stored answer of the player used in the answers hash
+    fn get_stored_answer_value(&self, quiz_id: QuizId, question_id: QuestionId, account_id: &AccountId, salt: Option<&str>) -> String {
+        let answer = self.answers.get(&QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, account_id.clone()))
+            .expect("Answer not found");
+        if QuizChain::is_late_answer(&answer) {
+            LATE_ANSWER.to_string()
+        } else {
+            self.get_revealed_answer_value(quiz_id, question_id, salt, &RevealedAnswer {
+                selected_option_ids: answer.selected_option_ids,
+                selected_text: answer.selected_text,
+                selected_pairs: answer.selected_pairs,
+            })
+        }
+    }
+
+    // Correct answers of the stored answers, answers of question pools are checked question by question
+    fn get_correct_answers(&self, quiz_id: QuizId, quiz: &Quiz, game: &Game, account_id: &AccountId) -> u16 {
+        QuizChain::get_game_question_ids(quiz, game)
+            .into_iter()
+            .filter(|question_id| {
+                let question = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, *question_id)).expect("Question not found");
+                let answer_to_hash = self.get_stored_answer_value(quiz_id, *question_id, account_id, QuizChain::get_answer_salt(quiz));
+                QuizChain::is_answer_correct(quiz, &question, &answer_to_hash)
+            })
+            .count() as u16
+    }
+
+    fn internal_finish_game(&mut self, game: &Game, quiz_id: QuizId, quiz: &mut Quiz) {
+        match quiz.finality_type {
+            QuizFinalityType::Direct if quiz.questions_per_game.is_some() =>
+                self.finalize_pool_game(game, quiz_id, quiz),
+            // games of quizzes with accepted answers are verified with verify_game after the success hash reveal
+            QuizFinalityType::Direct if self.is_verified_after_reveal(quiz_id, quiz) =>
+                self.internal_add_finished_player(quiz_id, env::predecessor_account_id()),
+            QuizFinalityType::Direct => {
+                self.stop_game(game.current_hash.clone(), &quiz_id);
+                self.internal_add_finished_player(quiz_id, env::predecessor_account_id());
+            }
+            QuizFinalityType::DelayedReveal => {
+                self.stop_game(game.current_hash.clone(), &quiz_id);
+                self.internal_add_finished_player(quiz_id, env::predecessor_account_id());
+            }
+            QuizFinalityType::CommitReveal =>
+                self.stop_game(game.current_hash.clone(), &quiz_id),
+        };
+    }
+
+    // Players who finished the game of a quiz with a committed success hash or a DelayedReveal quiz (or
+    // committed the answers of a CommitReveal quiz) in order. They share the rewards if the owner doesn't
+    // reveal the hash
+    pub(crate) fn internal_add_finished_player(&mut self, quiz_id: QuizId, account_id: AccountId) {
+        let mut finished_players = self.finished_players.get(&quiz_id)
+            .unwrap_or_else(|| Vector::new(StorageKey::FinishedPlayersByQuiz { quiz_id }));
+        finished_players.push(&account_id);
+        self.finished_players.insert(&quiz_id, &finished_players);
+    }
+
+    fn is_late_answer(answer: &Answer) -> bool {
+        answer.selected_option_ids.is_none() && answer.selected_text.is_none() && answer.selected_pairs.is_none()
+    }
+
     pub(crate) fn stop_game(&mut self, hash: Hash, quiz_id: &QuizId) {
         let index = &QuizResultByQuiz {
             quiz_id: *quiz_id,
@@ -107,32 +336,169 @@ impl QuizChain {
         self.quiz_results.insert(index, &accounts_with_same_result);
     }
 
-    pub(crate) fn finalize_game(&mut self, game: &Game, quiz_id: &QuizId, quiz: &mut Quiz) {
-        if game.current_hash == quiz.success_hash.clone().unwrap() {
-            //log!("All your answers are valid!");
-            if quiz.available_rewards_ids.len() > 0 {
-                if let Some((reward_id, other_reward_ids)) = quiz.available_rewards_ids.clone().split_first() {
-                    let reward_index = QuizChain::get_reward_by_quiz(*quiz_id, *reward_id);
-                    if let Some(mut reward) = self.rewards.get(&reward_index) {
-                        assert!(reward.winner_account_id.is_none(), "Reward already distributed");
-                        reward.winner_account_id = Some(env::predecessor_account_id());
-                        self.rewards.insert(&reward_index, &reward);
-                        //log!("Congratulations! You allowed to claim reward of {} yNEAR", reward.amount);
-
-                        quiz.available_rewards_ids = other_reward_ids.to_vec();
-                        if other_reward_ids.len() == 0 {
-                            quiz.status = QuizStatus::Finished;
-                            self.active_quizzes.remove(quiz_id);
-                        }
-                        quiz.distributed_rewards_ids.push(*reward_id);
-                        self.quizzes.insert(quiz_id, &quiz);
-                    }
+    // Players of question pools who answered all questions of their game correctly win the next reward at once
+    fn finalize_pool_game(&mut self, game: &Game, quiz_id: QuizId, quiz: &mut Quiz) {
+        if game.correct_answers == QuizChain::get_questions_per_game(quiz) {
+            self.internal_assign_next_reward(quiz_id, quiz);
+        }
+    }
+
+    fn internal_assign_next_reward(&mut self, quiz_id: QuizId, quiz: &mut Quiz) {
+        if let Some((reward_id, other_reward_ids)) = quiz.available_rewards_ids.clone().split_first() {
+            let reward_index = QuizChain::get_reward_by_quiz(quiz_id, *reward_id);
+            if let Some(mut reward) = self.rewards.get(&reward_index) {
+                assert!(reward.winner_account_id.is_none(), "Reward already distributed");
+                reward.winner_account_id = Some(env::predecessor_account_id());
+                QuizChain::internal_add_entry_fees_to_reward(quiz, &mut reward);
+                self.rewards.insert(&reward_index, &reward);
+
+                quiz.available_rewards_ids = other_reward_ids.to_vec();
+                quiz.distributed_rewards_ids.push(*reward_id);
+                if other_reward_ids.is_empty() {
+                    self.internal_set_quiz_status(quiz_id, quiz, QuizStatus::Finished);
+                } else {
+                    self.internal_update_active_quiz_prize(quiz_id, quiz);
                 }
+                self.quizzes.insert(&quiz_id, quiz);
+            }
+        }
+    }
 
+    // Accepted answers are salted with the salt of the success hash commitment, so games of such quizzes
+    // are verified only after the reveal. Anyone may verify a game finished before the reveal until the
+    // end of the reveal period, winners are rewarded by finalize_quiz in order of their finish
+    pub fn verify_game(&mut self, quiz_id: QuizId, account_id: ValidAccountId) -> bool {
+        let account_id: AccountId = account_id.into();
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            assert!(self.is_verified_after_reveal(quiz_id, &quiz), "Games of this quiz are verified on finish");
+            assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not in Progress");
+            if let Some(reveal_started_at) = quiz.reveal_started_at {
+                assert!(env::block_timestamp() <= QuizChain::get_answers_reveal_end(reveal_started_at), "Reveal period is over");
+
+                let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+                let game = self.games.get(&game_id).expect("Game wasn't started");
+                assert!(game.submitted_at.map(|submitted_at| submitted_at <= reveal_started_at).unwrap_or(false),
+                        "Game wasn't finished before the reveal");
+
+                let is_winner = self.get_stored_answers_hash(quiz_id, &quiz, &game, &account_id, quiz.salt.as_deref()) ==
+                    quiz.success_hash.clone().unwrap();
+
+                if is_winner {
+                    let index = QuizResultByQuiz { quiz_id, hash: quiz.success_hash.unwrap() };
+                    let mut winners = self.quiz_results.get(&index).unwrap_or_default();
+                    if !winners.contains(&account_id) {
+                        winners.push(account_id);
+                        self.quiz_results.insert(&index, &winners);
+                    }
+                }
+                is_winner
+            } else {
+                panic!("Success hash wasn't revealed");
             }
+        } else {
+            panic!("Quiz not found");
         }
-        else {
-            //log!("Something was wrong...");
+    }
+
+    // Verified players in order of their finish
+    pub(crate) fn get_verified_winners(&self, quiz_id: QuizId, quiz: &Quiz) -> Vec<AccountId> {
+        let mut winners: Vec<AccountId> = self.quiz_results.get(
+            &QuizResultByQuiz { quiz_id, hash: quiz.success_hash.clone().unwrap() }).unwrap_or_default();
+        winners.sort_by_cached_key(|account_id|
+            self.games.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.clone())).unwrap().submitted_at);
+        winners
+    }
+
+    pub(crate) fn is_verified_after_reveal(&self, quiz_id: QuizId, quiz: &Quiz) -> bool {
+        quiz.questions_per_game.is_none() && self.has_accepted_answers(quiz_id, quiz)
+    }
+
+    // Answer hashes of pool questions are checked when the answer is sent, so they are salted with the quiz
+    // secret. Accepted answers of other quizzes are salted with the salt of the success hash commitment
+    pub(crate) fn get_answer_salt(quiz: &Quiz) -> Option<&str> {
+        if quiz.questions_per_game.is_some() {
+            quiz.secret.as_deref()
+        } else {
+            quiz.salt.as_deref()
+        }
+    }
+
+    pub(crate) fn has_accepted_answers(&self, quiz_id: QuizId, quiz: &Quiz) -> bool {
+        (0..quiz.total_questions).any(|question_id|
+            self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id))
+                .map(|question| question.accepted_answer_hashes.is_some())
+                .unwrap_or(false))
+    }
+
+    pub(crate) fn get_questions_per_game(quiz: &Quiz) -> u16 {
+        quiz.questions_per_game.unwrap_or(quiz.total_questions)
+    }
+
+    // Ids of questions in the order they are asked in the given game
+    pub(crate) fn get_game_question_ids(quiz: &Quiz, game: &Game) -> Vec<QuestionId> {
+        if let (Some(questions_per_game), Some(seed)) = (quiz.questions_per_game, game.seed.as_ref()) {
+            let mut question_ids = QuizChain::get_shuffled_ids(seed, quiz.total_questions);
+            question_ids.truncate(questions_per_game as usize);
+            question_ids
+        } else {
+            (0..quiz.total_questions).collect()
+        }
+    }
+
+    pub(crate) fn get_game_question_id(quiz: &Quiz, game: &Game, index: u16) -> Option<QuestionId> {
+        if quiz.questions_per_game.is_some() {
+            QuizChain::get_game_question_ids(quiz, game).get(index as usize).copied()
+        } else {
+            Some(index)
+        }
+    }
+
+    // Canonical option ids in the order they are shown to the player, None if options are not shuffled
+    pub(crate) fn get_options_order(quiz: &Quiz, game: &Game, question_id: QuestionId, options_quantity: u16) -> Option<Vec<QuestionOptionId>> {
+        if let (true, Some(seed)) = (quiz.shuffle_options, game.seed.as_ref()) {
+            let mut data = seed.clone();
+            data.extend_from_slice(&question_id.to_le_bytes());
+            Some(QuizChain::get_shuffled_ids(&Sha256::digest(&data), options_quantity))
+        } else {
+            None
+        }
+    }
+
+    // Options of Ordering questions are hashed in the given order, other options are sorted
+    pub(crate) fn get_canonical_option_ids(question: &Question, mut option_ids: Vec<QuestionOptionId>) -> Vec<QuestionOptionId> {
+        if question.kind == QuestionKind::Ordering {
+            let mut unique_option_ids = option_ids.clone();
+            unique_option_ids.sort_unstable();
+            unique_option_ids.dedup();
+            assert_eq!(unique_option_ids.len(), option_ids.len(), "Options should not repeat");
+            assert_eq!(option_ids.len(), question.options_quantity as usize, "All options should be ordered");
+        } else {
+            option_ids.sort_unstable();
+        }
+        option_ids
+    }
+
+    // Answer of a single question salted with the salt returned by get_answer_salt
+    pub(crate) fn get_answer_hash(salt: &str, answer: &str) -> Hash {
+        QuizChain::get_hash(format!("{}{}", salt, answer))
+    }
+
+    pub(crate) fn get_new_game(quiz: &Quiz) -> Game {
+        Game {
+            answers_quantity: 0,
+            current_hash: QuizChain::get_hash(quiz.secret.clone().unwrap()),
+            question_started_at: env::block_timestamp(),
+            seed: if quiz.questions_per_game.is_some() || quiz.shuffle_options { Some(env::random_seed()) } else { None },
+            correct_answers: 0,
+            submitted_at: None,
+        }
+    }
+
+    pub(crate) fn is_answer_late(question: &Question, game: &Game) -> bool {
+        if let Some(time_limit) = question.time_limit {
+            env::block_timestamp() > game.question_started_at + time_limit * SECOND_IN_NANOSECONDS
+        } else {
+            false
         }
     }
 
@@ -141,21 +507,32 @@ impl QuizChain {
     }
 
     pub (crate) fn assert_game_available_to_play(status: &QuizStatus){
-        assert!([QuizStatus::InProgress, QuizStatus::Finished].contains(&status), "Quiz is not active");
+        assert!([QuizStatus::InProgress, QuizStatus::Finished, QuizStatus::Expired].contains(&status), "Quiz is not active");
     }
 
-    pub fn start_game(&mut self, quiz_id: QuizId, referrer_id: Option<ValidAccountId>) {
-        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+    // Entry fees in NEAR are attached to the call, entry fees in fungible tokens are paid with ft_transfer_call
+    #[payable]
+    pub fn start_game(&mut self, quiz_id: QuizId, referrer_id: Option<ValidAccountId>, invitation: Option<Invitation>) {
+        let account_id = env::predecessor_account_id();
+        self.assert_invitation(quiz_id, &account_id, invitation);
+        self.internal_request_game(quiz_id, account_id, referrer_id, env::attached_deposit(), None);
+    }
+
+    pub(crate) fn internal_start_game(&mut self, quiz_id: QuizId, account_id: AccountId, referrer_id: Option<ValidAccountId>,
+                                      entry_fee: Balance, token_account_id: Option<TokenAccountId>) {
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
             QuizChain::assert_game_available_to_play(&quiz.status);
-            let account_id = env::predecessor_account_id();
+            QuizChain::assert_quiz_is_open(&quiz);
 
             let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
             assert!(self.games.get(&game_id).is_none(), "Game already in progress");
-
-            let mut players: UnorderedSet<AccountId> = self.players.get(&quiz_id).unwrap_or(UnorderedSet::new(quiz_id.to_string().as_bytes().to_vec()));
-            players.insert(&account_id);
-            self.players.insert(&quiz_id, &players);
-            self.add_quiz_for_player(&quiz_id, account_id.clone());
+            self.assert_player_can_join(quiz_id, &quiz, &account_id);
+            if !self.has_free_player_place(quiz_id, &quiz) {
+                QuizChain::assert_valid_entry_fee(&quiz, entry_fee, &token_account_id);
+                self.internal_add_to_waitlist(quiz_id, account_id);
+                return;
+            }
+            self.internal_pay_entry_fee(quiz_id, &mut quiz, &account_id, entry_fee, &token_account_id);
 
             if let Some(valid_referrer_account_id) = referrer_id {
                 let referrer_id_value: AccountId = valid_referrer_account_id.into();
@@ -164,14 +541,19 @@ impl QuizChain {
                 }
             }
 
-            self.games.insert(&game_id,
-                              &Game {
-                                  answers_quantity: 0,
-                                  current_hash: QuizChain::get_hash(quiz.secret.unwrap()),
-                              });
+            self.internal_add_player(quiz_id, &quiz, account_id);
         }
     }
 
+    pub(crate) fn internal_add_player(&mut self, quiz_id: QuizId, quiz: &Quiz, account_id: AccountId) {
+        let mut players: UnorderedSet<AccountId> = self.players.get(&quiz_id).unwrap_or(UnorderedSet::new(quiz_id.to_string().as_bytes().to_vec()));
+        players.insert(&account_id);
+        self.players.insert(&quiz_id, &players);
+        self.add_quiz_for_player(&quiz_id, account_id.clone());
+
+        self.games.insert(&QuizChain::get_quiz_by_user(quiz_id, account_id), &QuizChain::get_new_game(quiz));
+    }
+
     fn internal_increase_referrer_stats(&mut self, quiz_id: QuizId, referrer_id: AccountId) {
         let mut already_invited_to_this_quiz = if let Some(already_invited) = self.affiliates.get(&quiz_id) {
             already_invited
@@ -194,11 +576,13 @@ impl QuizChain {
         if let Some(quiz) = self.quizzes.get(&quiz_id) {
             assert!(quiz.restart_allowed, "Restarts are now allowed for this quiz");
             assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not active");
+            assert!(quiz.success_hash_commitment.is_none(), "Restarts are not allowed for quizzes verified on finish");
+            assert!(quiz.questions_per_game.is_none(), "Restarts are not allowed for question pools");
 
             let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
 
             if let Some(game) = self.games.get(&game_id) {
-                assert_eq!(game.answers_quantity, quiz.total_questions, "Current game is not finished");
+                assert_eq!(game.answers_quantity, QuizChain::get_questions_per_game(&quiz), "Current game is not finished");
 
                 for reward_id in &quiz.distributed_rewards_ids {
                     if let Some(reward) = self.rewards.get(&QuizChain::get_reward_by_quiz(quiz_id, *reward_id)) {
@@ -208,11 +592,7 @@ impl QuizChain {
                     }
                 }
 
-                self.games.insert(&game_id,
-                                  &Game {
-                                      answers_quantity: 0,
-                                      current_hash: QuizChain::get_hash(quiz.secret.unwrap()),
-                                  });
+                self.games.insert(&game_id, &QuizChain::get_new_game(&quiz));
 
                 log!("Game restarted");
             }
@@ -224,25 +604,28 @@ impl QuizChain {
 
     // Test reasons only TODO remove
     #[private]
+    #[payable]
     pub fn start_game_for_account_id(&mut self, quiz_id: QuizId, account_id: AccountId) {
-        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+            assert!(quiz.eligibility_gates.is_empty(), "Eligibility of players is checked in start_game");
+            assert!(quiz.invitation_key.is_none(), "Invitations are checked in start_game");
+            self.assert_allowlisted(quiz_id, &quiz, &account_id);
+            self.assert_player_can_join(quiz_id, &quiz, &account_id);
+            assert!(self.has_free_player_place(quiz_id, &quiz), "Quiz is full");
             let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+            self.internal_pay_entry_fee(quiz_id, &mut quiz, &account_id, env::attached_deposit(), &None);
 
             let mut players: UnorderedSet<AccountId> = self.players.get(&quiz_id).unwrap_or(UnorderedSet::new(quiz_id.to_string().as_bytes().to_vec()));
 
             players.insert(&account_id);
             self.players.insert(&quiz_id, &players);
 
-            self.games.insert(&game_id,
-                              &Game {
-                                  answers_quantity: 0,
-                                  current_hash: QuizChain::get_hash(quiz.secret.unwrap()),
-                              });
+            self.games.insert(&game_id, &QuizChain::get_new_game(&quiz));
         }
     }
 
     pub fn get_quiz_stats(&self, quiz_id: QuizId, from_index: usize, limit: usize) -> Option<Vec<StatsOutput>> {
-        if let Some(player_ids) = self.players.get(&quiz_id) {
+        if let (Some(quiz), Some(player_ids)) = (self.quizzes.get(&quiz_id), self.players.get(&quiz_id)) {
             let player_ids_qty = player_ids.len() as usize;
             let mut stats: Vec<StatsOutput> = Vec::new();
             assert!(from_index <= player_ids_qty, "Illegal from_index");
@@ -251,8 +634,13 @@ impl QuizChain {
             for player_index in from_index..limit_id {
                 if let Some(player_id) = player_account_ids.get(player_index as u64) {
                     if let Some(game) = self.games.get(&QuizChain::get_quiz_by_user(quiz_id, player_id.clone())) {
-                        let last_answer_timestamp = if game.answers_quantity > 0 {
-                            let last_answer = self.answers.get(&QuizChain::get_answer_by_quiz_by_question(quiz_id, game.answers_quantity - 1, player_id.clone()));
+                        let last_question_id = if game.answers_quantity > 0 {
+                            QuizChain::get_game_question_id(&quiz, &game, game.answers_quantity - 1)
+                        } else {
+                            None
+                        };
+                        let last_answer_timestamp = if let Some(last_question_id) = last_question_id {
+                            let last_answer = self.answers.get(&QuizChain::get_answer_by_quiz_by_question(quiz_id, last_question_id, player_id.clone()));
                             if let Some(last_answer_value) = last_answer {
                                 Some(last_answer_value.timestamp)
                             } else {
@@ -280,6 +668,70 @@ impl QuizChain {
         self.games.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.into()))
     }
 
+    // Seconds left to answer the current question, None if the question has no time limit
+    pub fn get_question_time_left(&self, quiz_id: QuizId, account_id: ValidAccountId) -> Option<u64> {
+        if let (Some(quiz), Some(game)) = (self.quizzes.get(&quiz_id), self.games.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.into()))) {
+            if let Some(question_id) = QuizChain::get_game_question_id(&quiz, &game, game.answers_quantity) {
+                if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)) {
+                    if let Some(time_limit) = question.time_limit {
+                        let deadline = game.question_started_at + time_limit * SECOND_IN_NANOSECONDS;
+                        return Some(deadline.saturating_sub(env::block_timestamp()) / SECOND_IN_NANOSECONDS);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Questions of the game in the order the player has to answer them
+    pub fn get_game_questions(&self, quiz_id: QuizId, account_id: ValidAccountId, language: Option<String>) -> Vec<QuestionOutput> {
+        if let (Some(quiz), Some(game)) = (self.quizzes.get(&quiz_id), self.games.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.into()))) {
+            let translation = self.get_translation(quiz_id, &language);
+            QuizChain::get_game_question_ids(&quiz, &game)
+                .into_iter()
+                .filter_map(|question_id| self.get_question_output(quiz_id, &quiz, question_id))
+                .map(|mut question| {
+                    QuizChain::translate_question(&mut question, &translation);
+                    question.question_options = QuizChain::get_game_question_options_internal(&quiz, &game, question.id, question.question_options);
+                    question
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    // Options of the question in the order they are shown to the player. Ids of the options
+    // are positions in this order and should be used in send_answer
+    pub fn get_game_question_options(&self, quiz_id: QuizId, question_id: QuestionId, account_id: ValidAccountId,
+                                     language: Option<String>) -> Vec<QuestionOptionOutput> {
+        if let (Some(quiz), Some(game)) = (self.quizzes.get(&quiz_id), self.games.get(&QuizChain::get_quiz_by_user(quiz_id, account_id.into()))) {
+            if let Some(mut question) = self.get_question_output(quiz_id, &quiz, question_id) {
+                QuizChain::translate_question(&mut question, &self.get_translation(quiz_id, &language));
+                return QuizChain::get_game_question_options_internal(&quiz, &game, question_id, question.question_options);
+            }
+        }
+        Vec::new()
+    }
+
+    fn get_game_question_options_internal(quiz: &Quiz, game: &Game, question_id: QuestionId,
+                                          question_options: Vec<QuestionOptionOutput>) -> Vec<QuestionOptionOutput> {
+        if let Some(options_order) = QuizChain::get_options_order(quiz, game, question_id, question_options.len() as u16) {
+            let mut question_options: Vec<Option<QuestionOptionOutput>> = question_options.into_iter().map(Some).collect();
+            options_order.iter().enumerate()
+                .filter_map(|(index, question_option_id)| {
+                    question_options.get_mut(*question_option_id as usize).and_then(Option::take)
+                        .map(|mut question_option| {
+                            question_option.id = index as QuestionOptionId;
+                            question_option
+                        })
+                })
+                .collect()
+        } else {
+            question_options
+        }
+    }
+
     pub fn get_answer(&self, quiz_id: QuizId, question_id: QuestionId, account_id: ValidAccountId) -> Option<Answer> {
         self.answers.get(&QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, account_id.into()))
     }
@@ -300,7 +752,7 @@ impl QuizChain {
 
     pub fn get_answers(&self, quiz_id: QuizId, account_id: ValidAccountId) -> Vec<AnswerOutput> {
         if let Some(quiz) = self.quizzes.get(&quiz_id) {
-            let revealed_answers = if let Some(revealed_answers_unwrapped) = quiz.revealed_answers {
+            let revealed_answers = if let Some(revealed_answers_unwrapped) = quiz.revealed_answers.clone() {
                 revealed_answers_unwrapped
             }
             else{
@@ -309,7 +761,7 @@ impl QuizChain {
             let revealed_answers_found = revealed_answers.len() > 0;
 
             let mut answers: Vec<AnswerOutput> = Vec::new();
-            let questions = self.get_questions_by_quiz(quiz_id);
+            let questions = self.get_questions_by_quiz(quiz_id, None);
             for question in &questions {
                 let answer = self.answers.get(
                     &QuizChain::get_answer_by_quiz_by_question(quiz_id, question.id, account_id.clone().into()));
@@ -317,9 +769,22 @@ impl QuizChain {
 
                     let is_correct = if revealed_answers_found {
                         let revealed_answer = &revealed_answers[(question.id as usize)];
+                        let selected_text_is_correct = match (answer_unwrapped.selected_text.as_ref(), revealed_answer.selected_text.as_ref()) {
+                            (Some(text), Some(revealed_text)) => {
+                                // accepted answers and numbers are compared by the value used in the answers hash
+                                QuizChain::get_text_answer(QuizChain::get_answer_salt(&quiz), &question.question, text).1 ==
+                                    QuizChain::get_text_answer(QuizChain::get_answer_salt(&quiz), &question.question, revealed_text).1
+                            }
+                            (text, revealed_text) => text == revealed_text
+                        };
+                        let revealed_option_ids = revealed_answer.selected_option_ids.clone()
+                            .map(|option_ids| QuizChain::get_canonical_option_ids(&question.question, option_ids));
+                        let revealed_pairs = revealed_answer.selected_pairs.clone()
+                            .map(|pairs| self.get_matching_answer(quiz_id, question.id, &question.question, pairs).0);
                         Some(
-                            answer_unwrapped.selected_option_ids == revealed_answer.selected_option_ids &&
-                            answer_unwrapped.selected_text == revealed_answer.selected_text)
+                            answer_unwrapped.selected_option_ids == revealed_option_ids &&
+                            selected_text_is_correct &&
+                            answer_unwrapped.selected_pairs == revealed_pairs)
                     }
                     else{
                         None
@@ -329,6 +794,7 @@ impl QuizChain {
                         id: question.id,
                         selected_option_ids: answer_unwrapped.selected_option_ids,
                         selected_text: answer_unwrapped.selected_text,
+                        selected_pairs: answer_unwrapped.selected_pairs,
                         timestamp: answer_unwrapped.timestamp,
                         is_correct
                     })
@@ -21,4 +21,23 @@ impl QuizChain {
     pub(crate) fn get_answer_by_quiz_by_question(quiz_id: QuizId, question_id: QuestionId, account_id: AccountId) -> AnswerByQuizByQuestionByUser {
         AnswerByQuizByQuestionByUser { quiz_id, question_id, account_id }
     }
-}
\ No newline at end of file
+
+    pub(crate) fn get_translation_by_quiz(quiz_id: QuizId, language: String) -> TranslationByQuiz {
+        TranslationByQuiz { quiz_id, language }
+    }
+
+    // Deterministic Fisher-Yates shuffle of ids 0..quantity based on the given seed
+    pub(crate) fn get_shuffled_ids(seed: &[u8], quantity: u16) -> Vec<u16> {
+        let mut ids: Vec<u16> = (0..quantity).collect();
+        for index in (1..ids.len()).rev() {
+            let mut data = seed.to_vec();
+            data.extend_from_slice(&(index as u64).to_le_bytes());
+            let digest = Sha256::digest(&data);
+            let mut random_bytes = [0u8; 8];
+            random_bytes.copy_from_slice(&digest[..8]);
+            let random_index = (u64::from_le_bytes(random_bytes) % (index as u64 + 1)) as usize;
+            ids.swap(index, random_index);
+        }
+        ids
+    }
+}
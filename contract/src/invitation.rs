@@ -0,0 +1,44 @@
+use std::convert::TryFrom;
+use ed25519_dalek::{Signature, Verifier};
+use near_sdk::json_types::{Base58PublicKey, Base64VecU8};
+
+use crate::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Invitation {
+    expires_at: Timestamp,
+    signature: Base64VecU8,
+}
+
+#[near_bindgen]
+impl QuizChain {
+    // Quizzes with an invitation key may be played only with invitations signed by this key
+    pub fn set_invitation_key(&mut self, quiz_id: QuizId, invitation_key: Option<Base58PublicKey>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        if let Some(invitation_key) = invitation_key.as_ref() {
+            assert!(invitation_key.0.len() == 33 && invitation_key.0[0] == 0, "Only ed25519 keys are supported");
+            ed25519_dalek::PublicKey::from_bytes(&invitation_key.0[1..]).expect("Illegal invitation key");
+        }
+
+        quiz.invitation_key = invitation_key.map(|invitation_key| invitation_key.into());
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Invitation is an ed25519 signature of "<quiz_id>:<account_id>:<expires_at>"
+    pub(crate) fn assert_invitation(&self, quiz_id: QuizId, account_id: &AccountId, invitation: Option<Invitation>) {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            if let Some(invitation_key) = quiz.invitation_key {
+                let invitation = invitation.expect("Invitation is missing");
+                assert!(env::block_timestamp() < invitation.expires_at, "Invitation expired");
+
+                let public_key = ed25519_dalek::PublicKey::from_bytes(&invitation_key[1..]).expect("Illegal invitation key");
+                let signature = Signature::try_from(invitation.signature.0.as_slice()).expect("Illegal signature");
+                let message = format!("{}:{}:{}", quiz_id, account_id, invitation.expires_at);
+                assert!(public_key.verify(message.as_bytes(), &signature).is_ok(), "Invitation is not valid");
+            }
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+}
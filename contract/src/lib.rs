@@ -1,11 +1,13 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{env, near_bindgen, ext_contract, AccountId, Balance, BorshStorageKey, PanicOnDefault,
-               PromiseOrValue, Promise, Timestamp, log, assert_one_yocto};
-use near_sdk::collections::{LookupMap, UnorderedSet, LookupSet, UnorderedMap};
+               PromiseOrValue, Promise, PublicKey, Timestamp, log, assert_one_yocto};
+use near_sdk::collections::{LookupMap, UnorderedSet, LookupSet, UnorderedMap, TreeMap, Vector};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::json_types::{ValidAccountId, WrappedBalance};
 use sha2::{Sha256, Digest};
 
+use crate::migrate::Migration;
+
 pub use crate::quiz::*;
 mod quiz;
 mod internal;
@@ -14,10 +16,28 @@ mod game;
 mod migrate;
 mod ft;
 mod ft_callbacks;
+mod commit_reveal;
+mod quiz_edit;
+mod quiz_clone;
+mod text_answer;
+mod numeric_answer;
+mod matching_answer;
+mod quiz_translation;
+mod quiz_index;
+mod active_quizzes;
+mod scheduled_activation;
+mod entry_fee;
+mod eligibility;
+mod invitation;
+mod allowlist;
+mod waitlist;
+#[cfg(test)]
+mod tests;
 
 type QuizId = u64;
 type QuestionId = u16;
 type QuestionOptionId = u16;
+type QuestionOptionPair = (QuestionOptionId, QuestionOptionId);
 type AnswerId = u16;
 type RewardId = u16;
 type Secret = String;
@@ -25,6 +45,8 @@ type Hash = String;
 type TokenAccountId = AccountId;
 
 const NEAR: &str = "near";
+const DAY_IN_NANOSECONDS: Timestamp = 86400000000000;
+const SECOND_IN_NANOSECONDS: Timestamp = 1000000000;
 
 near_sdk::setup_alloc!();
 
@@ -54,6 +76,29 @@ pub struct QuizChain {
 
     affiliates: LookupMap<QuizId, UnorderedMap<AccountId, u64>>,
     total_affiliates: UnorderedMap<AccountId, u64>,
+
+    answer_commitments: LookupMap<QuizByUser, AnswerCommitment>,
+    clone_licenses: LookupMap<QuizId, UnorderedSet<AccountId>>,
+    quiz_translations: LookupMap<TranslationByQuiz, QuizTranslation>,
+
+    quizzes_by_tag: LookupMap<String, UnorderedSet<QuizId>>,
+    quizzes_by_language: LookupMap<String, UnorderedSet<QuizId>>,
+    quizzes_by_token: LookupMap<TokenAccountId, UnorderedSet<QuizId>>,
+    quizzes_by_status: LookupMap<QuizStatus, UnorderedSet<QuizId>>,
+
+    active_quizzes_by_id: TreeMap<QuizId, QuizId>,
+    active_quizzes_by_prize: TreeMap<(Balance, QuizId), QuizId>,
+    active_quizzes_by_end: TreeMap<(Timestamp, QuizId), QuizId>,
+    active_quizzes_prizes: LookupMap<QuizId, Balance>,
+
+    entry_fee_payments: LookupMap<QuizByUser, Balance>,
+    allowlists: LookupMap<QuizId, UnorderedSet<AccountId>>,
+    waitlists: LookupMap<QuizId, Waitlist>,
+    waitlisted_accounts: LookupSet<QuizByUser>,
+    released_players: LookupSet<QuizByUser>,
+    finished_players: LookupMap<QuizId, Vector<AccountId>>,
+
+    migration: Option<Migration>,
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -61,25 +106,49 @@ pub struct Quiz {
     title: Option<String>,
     description: Option<String>,
     language: Option<String>,
+    languages: Vec<String>,
+    tags: Vec<String>,
     finality_type: QuizFinalityType,
 
     owner_id: AccountId,
     status: QuizStatus,
 
     total_questions: u16,
+    questions_per_game: Option<u16>,
+    shuffle_options: bool,
 
     available_rewards_ids: Vec<RewardId>,
     distributed_rewards_ids: Vec<RewardId>,
 
     secret: Option<Secret>,
     success_hash: Option<Hash>,
+    success_hash_commitment: Option<Hash>,
+    salt: Option<String>,
 
     revealed_answers: Option<Vec<RevealedAnswer>>,
+    reveal_started_at: Option<Timestamp>,
     sponsor_account_id: Option<AccountId>,
     funded_amount: Option<Balance>,
     restart_allowed: bool,
+    starts_at: Option<Timestamp>,
+    ends_at: Option<Timestamp>,
     timestamp: Option<Timestamp>,
     token_account_id: Option<TokenAccountId>,
+    clone_policy: ClonePolicy,
+    activated_at: Option<Timestamp>,
+    cancel_grace_period: Option<Timestamp>,
+    scheduled_activation: Option<ScheduledActivation>,
+    revisable_answers: bool,
+    entry_fee: Option<EntryFee>,
+    entry_fees_quantity: u64,
+    entry_fees_prize_pool: Balance,
+    entry_fees_withdrawn: bool,
+    eligibility_gates: Vec<EligibilityGate>,
+    invitation_key: Option<PublicKey>,
+    allowlist_only: bool,
+    max_players: Option<u64>,
+    waitlist_enabled: bool,
+    player_timeout: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
@@ -88,14 +157,38 @@ pub struct Question {
     kind: QuestionKind,
     content: String,
     hint: Option<String>,
-    options_quantity: u16
+    options_quantity: u16,
+    time_limit: Option<u64>,
+    answer_hash: Option<Hash>,
+    text_normalization: Option<TextNormalization>,
+    accepted_answer_hashes: Option<Vec<Hash>>,
+    numeric_settings: Option<NumericSettings>
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TextNormalization {
+    trim: bool,
+    collapse_whitespace: bool,
+    strip_punctuation: bool,
+    unicode_normalization: bool,
+    fold_diacritics: bool,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NumericSettings {
+    decimals: u8,
+    bucket_width: u64,
+    bucket_offset: u64,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct QuestionOption {
     content: String,
-    kind: QuestionOptionKind
+    kind: QuestionOptionKind,
+    column: Option<QuestionOptionColumn>
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
@@ -104,6 +197,11 @@ pub struct QuestionInput {
     kind: QuestionKind,
     content: String,
     hint: Option<String>,
+    time_limit: Option<u64>,
+    answer_hash: Option<Hash>,
+    text_normalization: Option<TextNormalization>,
+    accepted_answer_hashes: Option<Vec<Hash>>,
+    numeric_settings: Option<NumericSettings>,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
@@ -135,6 +233,7 @@ pub struct QuestionOptionOutput {
     id: QuestionId,
     content: String,
     kind: QuestionOptionKind,
+    column: Option<QuestionOptionColumn>,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
@@ -159,6 +258,29 @@ pub struct AnswerByQuizByQuestionByUser {
     account_id: AccountId
 }
 
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TranslationByQuiz {
+    quiz_id: QuizId,
+    language: String
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuizTranslation {
+    title: String,
+    description: Option<String>,
+    questions: Vec<QuestionTranslation>
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuestionTranslation {
+    content: String,
+    hint: Option<String>,
+    question_options: Vec<String>
+}
+
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct QuestionByQuiz {
@@ -192,7 +314,11 @@ pub struct RewardByQuiz {
 #[serde(crate = "near_sdk::serde")]
 pub struct Game {
     answers_quantity: u16,
-    current_hash: Hash
+    current_hash: Hash,
+    question_started_at: Timestamp,
+    seed: Option<Vec<u8>>,
+    correct_answers: u16,
+    submitted_at: Option<Timestamp>
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
@@ -200,14 +326,54 @@ pub struct Game {
 pub struct Answer {
     selected_option_ids: Option<Vec<QuestionOptionId>>,
     selected_text: Option<String>,
+    selected_pairs: Option<Vec<QuestionOptionPair>>,
     timestamp: Timestamp
 }
 
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnswerCommitment {
+    hash: Hash,
+    timestamp: Timestamp,
+    revealed: bool
+}
+
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct RevealedAnswer {
     selected_option_ids: Option<Vec<QuestionOptionId>>,
-    selected_text: Option<String>
+    selected_text: Option<String>,
+    selected_pairs: Option<Vec<QuestionOptionPair>>
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ScheduledActivation {
+    activation_at: Timestamp,
+    secret_commitment: Hash,
+    success_hash: Option<Hash>,
+    success_hash_commitment: Option<Hash>,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EligibilityGate {
+    FtBalance { token_account_id: TokenAccountId, min_balance: WrappedBalance },
+    NftOwnership { nft_account_id: AccountId },
+    Registry { registry_account_id: AccountId, method_name: String },
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Waitlist {
+    promoted_quantity: u64,
+    account_ids: Vector<AccountId>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct EntryFee {
+    amount: Balance,
+    prize_pool_rate: u16,
+    owner_rate: u16,
+    service_rate: u16,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
@@ -222,7 +388,8 @@ pub struct Reward {
 #[serde(crate = "near_sdk::serde")]
 pub enum QuizFinalityType {
     Direct,
-    DelayedReveal
+    DelayedReveal,
+    CommitReveal
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
@@ -231,7 +398,9 @@ pub enum QuizStatus {
     Funded,
     Locked,
     InProgress,
-    Finished
+    Finished,
+    Cancelled,
+    Expired
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
@@ -239,7 +408,18 @@ pub enum QuizStatus {
 pub enum QuestionKind {
     OneChoice,
     MultipleChoice,
-    Text
+    Text,
+    Numeric,
+    Ordering,
+    Matching
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ClonePolicy {
+    OwnerOnly,
+    Public,
+    Licensed
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Copy, Clone)]
@@ -250,6 +430,13 @@ pub enum QuestionOptionKind {
     Html
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum QuestionOptionColumn {
+    Left,
+    Right
+}
+
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     Quizzes,
@@ -275,6 +462,37 @@ enum StorageKey {
     Affiliates,
     AffiliatesByQuiz { quiz_id: u64 },
     TotalAffiliates,
+
+    QuizzesV2,
+    AnswerCommitments,
+    QuestionsV1,
+    GamesV1,
+    CloneLicenses,
+    CloneLicensesByQuiz { quiz_id: u64 },
+    QuestionOptionsV1,
+    AnswersV1,
+    QuizTranslations,
+    QuizzesByTag,
+    QuizzesByTagSet { tag: String },
+    QuizzesByLanguage,
+    QuizzesByLanguageSet { language: String },
+    QuizzesByToken,
+    QuizzesByTokenSet { token_account_id: TokenAccountId },
+    QuizzesByStatus,
+    QuizzesByStatusSet { status: QuizStatus },
+    ActiveQuizzesById,
+    ActiveQuizzesByPrize,
+    ActiveQuizzesByEnd,
+    ActiveQuizzesPrizes,
+    EntryFeePayments,
+    Allowlists,
+    AllowlistsByQuiz { quiz_id: u64 },
+    Waitlists,
+    WaitlistsByQuiz { quiz_id: u64 },
+    WaitlistedAccounts,
+    ReleasedPlayers,
+    FinishedPlayers,
+    FinishedPlayersByQuiz { quiz_id: u64 },
 }
 
 #[near_bindgen]
@@ -305,6 +523,29 @@ impl QuizChain {
 
             affiliates: LookupMap::new(StorageKey::Affiliates),
             total_affiliates: UnorderedMap::new(StorageKey::TotalAffiliates),
+
+            answer_commitments: LookupMap::new(StorageKey::AnswerCommitments),
+            clone_licenses: LookupMap::new(StorageKey::CloneLicenses),
+            quiz_translations: LookupMap::new(StorageKey::QuizTranslations),
+
+            quizzes_by_tag: LookupMap::new(StorageKey::QuizzesByTag),
+            quizzes_by_language: LookupMap::new(StorageKey::QuizzesByLanguage),
+            quizzes_by_token: LookupMap::new(StorageKey::QuizzesByToken),
+            quizzes_by_status: LookupMap::new(StorageKey::QuizzesByStatus),
+
+            active_quizzes_by_id: TreeMap::new(StorageKey::ActiveQuizzesById),
+            active_quizzes_by_prize: TreeMap::new(StorageKey::ActiveQuizzesByPrize),
+            active_quizzes_by_end: TreeMap::new(StorageKey::ActiveQuizzesByEnd),
+            active_quizzes_prizes: LookupMap::new(StorageKey::ActiveQuizzesPrizes),
+
+            entry_fee_payments: LookupMap::new(StorageKey::EntryFeePayments),
+            allowlists: LookupMap::new(StorageKey::Allowlists),
+            waitlists: LookupMap::new(StorageKey::Waitlists),
+            waitlisted_accounts: LookupSet::new(StorageKey::WaitlistedAccounts),
+            released_players: LookupSet::new(StorageKey::ReleasedPlayers),
+            finished_players: LookupMap::new(StorageKey::FinishedPlayers),
+
+            migration: None,
         }
     }
 }
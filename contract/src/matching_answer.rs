@@ -0,0 +1,57 @@
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    // Returns pairs sorted by the left option and the value used in the answers hash.
+    // Every left option has to be matched with a different right option
+    pub(crate) fn get_matching_answer(&self, quiz_id: QuizId, question_id: QuestionId, question: &Question,
+                                      mut pairs: Vec<QuestionOptionPair>) -> (Vec<QuestionOptionPair>, String) {
+        pairs.sort_unstable();
+
+        let mut right_option_ids: Vec<QuestionOptionId> = pairs.iter().map(|(_, right_option_id)| *right_option_id).collect();
+        right_option_ids.sort_unstable();
+        right_option_ids.dedup();
+        assert_eq!(right_option_ids.len(), pairs.len(), "Options should not repeat");
+
+        let left_options_quantity = (0..question.options_quantity)
+            .filter_map(|question_option_id| self.question_options.get(&QuizChain::get_question_option_by_quiz(quiz_id, question_id, question_option_id)))
+            .filter(|question_option| question_option.column == Some(QuestionOptionColumn::Left))
+            .count();
+        assert_eq!(left_options_quantity, pairs.len(), "All left options should be matched");
+
+        let mut answer_string: String = "".to_string();
+        let mut previous_left_option_id: Option<QuestionOptionId> = None;
+        for (left_option_id, right_option_id) in &pairs {
+            assert_ne!(previous_left_option_id, Some(*left_option_id), "Options should not repeat");
+            previous_left_option_id = Some(*left_option_id);
+
+            let left_option = self.get_question_option_in_column(quiz_id, question_id, *left_option_id, QuestionOptionColumn::Left);
+            let right_option = self.get_question_option_in_column(quiz_id, question_id, *right_option_id, QuestionOptionColumn::Right);
+            answer_string = format!("{}{}={};", answer_string, left_option.content, right_option.content);
+        }
+
+        (pairs, answer_string.to_lowercase())
+    }
+
+    fn get_question_option_in_column(&self, quiz_id: QuizId, question_id: QuestionId, question_option_id: QuestionOptionId,
+                                     column: QuestionOptionColumn) -> QuestionOption {
+        if let Some(question_option) = self.question_options.get(&QuizChain::get_question_option_by_quiz(quiz_id, question_id, question_option_id)) {
+            assert!(question_option.column == Some(column), "Option {} is in the wrong column", question_option_id);
+            question_option
+        } else {
+            panic!("Question option not found");
+        }
+    }
+
+    pub(crate) fn assert_valid_question_options(kind: QuestionKind, question_options: &[QuestionOption]) {
+        if kind == QuestionKind::Matching {
+            let left_options_quantity = question_options.iter().filter(|question_option| question_option.column == Some(QuestionOptionColumn::Left)).count();
+            let right_options_quantity = question_options.iter().filter(|question_option| question_option.column == Some(QuestionOptionColumn::Right)).count();
+            assert_eq!(left_options_quantity + right_options_quantity, question_options.len(), "Every option should have a column");
+            assert!(left_options_quantity > 0, "Left options are missing");
+            assert!(right_options_quantity >= left_options_quantity, "Not enough right options");
+        } else {
+            assert!(question_options.iter().all(|question_option| question_option.column.is_none()), "Only Matching questions support option columns");
+        }
+    }
+}
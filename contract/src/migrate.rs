@@ -1,5 +1,74 @@
 use crate::*;
 
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct QuizOld {
+    title: Option<String>,
+    description: Option<String>,
+    language: Option<String>,
+    finality_type: QuizFinalityType,
+    owner_id: AccountId,
+    status: QuizStatus,
+    total_questions: u16,
+    available_rewards_ids: Vec<RewardId>,
+    distributed_rewards_ids: Vec<RewardId>,
+    secret: Option<Secret>,
+    success_hash: Option<Hash>,
+    revealed_answers: Option<Vec<RevealedAnswerOld>>,
+    sponsor_account_id: Option<AccountId>,
+    funded_amount: Option<Balance>,
+    restart_allowed: bool,
+    timestamp: Option<Timestamp>,
+    token_account_id: Option<TokenAccountId>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RevealedAnswerOld {
+    selected_option_ids: Option<Vec<QuestionOptionId>>,
+    selected_text: Option<String>
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct QuestionOptionOld {
+    content: String,
+    kind: QuestionOptionKind
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AnswerOld {
+    selected_option_ids: Option<Vec<QuestionOptionId>>,
+    selected_text: Option<String>,
+    timestamp: Timestamp
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct QuestionOld {
+    kind: QuestionKind,
+    content: String,
+    hint: Option<String>,
+    options_quantity: u16
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct GameOld {
+    answers_quantity: u16,
+    current_hash: Hash
+}
+
+// Collections of the state before migrate_3 and the position of migrate_3_batch in them
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Migration {
+    quizzes: LookupMap<QuizId, QuizOld>,
+    questions: LookupMap<QuestionByQuiz, QuestionOld>,
+    question_options: LookupMap<QuestionOptionByQuiz, QuestionOptionOld>,
+    games: LookupMap<QuizByUser, GameOld>,
+    answers: LookupMap<AnswerByQuizByQuestionByUser, AnswerOld>,
+
+    next_quiz_id: QuizId,
+    quiz_id: QuizId,
+    question_id: QuestionId,
+    player_index: u64,
+}
+
 #[near_bindgen]
 impl QuizChain {
 
@@ -54,20 +123,44 @@ impl QuizChain {
                                        title: Some(quiz.title),
                                        description: Some(quiz.description),
                                        language: None,
+                                       languages: Vec::new(),
+                                       tags: Vec::new(),
                                        finality_type: QuizFinalityType::Direct,
                                        owner_id: quiz.owner_id,
                                        status: QuizStatus::InProgress,
                                        total_questions: quiz.total_questions,
+                                       questions_per_game: None,
+                                       shuffle_options: false,
                                        available_rewards_ids: quiz.available_rewards_ids,
                                        distributed_rewards_ids: quiz.distributed_rewards_ids,
                                        secret: quiz.secret,
                                        success_hash: quiz.success_hash,
+                                       success_hash_commitment: None,
+                                       salt: None,
                                        revealed_answers: quiz.revealed_answers,
+                                       reveal_started_at: None,
                                        sponsor_account_id: None,
                                        funded_amount: None,
                                        restart_allowed: false,
+                                       starts_at: None,
+                                       ends_at: None,
                                        timestamp: None,
-                                       token_account_id: Some(QuizChain::unwrap_token_id(&None))
+                                       token_account_id: Some(QuizChain::unwrap_token_id(&None)),
+                                       clone_policy: ClonePolicy::OwnerOnly,
+                                       activated_at: None,
+                                       cancel_grace_period: None,
+                                       scheduled_activation: None,
+                                       revisable_answers: false,
+                                       entry_fee: None,
+                                       entry_fees_quantity: 0,
+                                       entry_fees_prize_pool: 0,
+                                       entry_fees_withdrawn: false,
+                                       eligibility_gates: Vec::new(),
+                                       invitation_key: None,
+                                       allowlist_only: false,
+                                       max_players: None,
+                                       waitlist_enabled: false,
+                                       player_timeout: None,
                                    });
             }
         }
@@ -96,6 +189,28 @@ impl QuizChain {
 
             affiliates: LookupMap::new(StorageKey::Affiliates),
             total_affiliates: UnorderedMap::new(StorageKey::TotalAffiliates),
+
+            answer_commitments: LookupMap::new(StorageKey::AnswerCommitments),
+            clone_licenses: LookupMap::new(StorageKey::CloneLicenses),
+            quiz_translations: LookupMap::new(StorageKey::QuizTranslations),
+            quizzes_by_tag: LookupMap::new(StorageKey::QuizzesByTag),
+            quizzes_by_language: LookupMap::new(StorageKey::QuizzesByLanguage),
+            quizzes_by_token: LookupMap::new(StorageKey::QuizzesByToken),
+            quizzes_by_status: LookupMap::new(StorageKey::QuizzesByStatus),
+
+            active_quizzes_by_id: TreeMap::new(StorageKey::ActiveQuizzesById),
+            active_quizzes_by_prize: TreeMap::new(StorageKey::ActiveQuizzesByPrize),
+            active_quizzes_by_end: TreeMap::new(StorageKey::ActiveQuizzesByEnd),
+            active_quizzes_prizes: LookupMap::new(StorageKey::ActiveQuizzesPrizes),
+
+            entry_fee_payments: LookupMap::new(StorageKey::EntryFeePayments),
+            allowlists: LookupMap::new(StorageKey::Allowlists),
+            waitlists: LookupMap::new(StorageKey::Waitlists),
+            waitlisted_accounts: LookupSet::new(StorageKey::WaitlistedAccounts),
+            released_players: LookupSet::new(StorageKey::ReleasedPlayers),
+            finished_players: LookupMap::new(StorageKey::FinishedPlayers),
+
+            migration: None,
         }
     }
 
@@ -152,6 +267,292 @@ impl QuizChain {
 
             affiliates: LookupMap::new(StorageKey::Affiliates),
             total_affiliates: UnorderedMap::new(StorageKey::TotalAffiliates),
+
+            answer_commitments: LookupMap::new(StorageKey::AnswerCommitments),
+            clone_licenses: LookupMap::new(StorageKey::CloneLicenses),
+            quiz_translations: LookupMap::new(StorageKey::QuizTranslations),
+            quizzes_by_tag: LookupMap::new(StorageKey::QuizzesByTag),
+            quizzes_by_language: LookupMap::new(StorageKey::QuizzesByLanguage),
+            quizzes_by_token: LookupMap::new(StorageKey::QuizzesByToken),
+            quizzes_by_status: LookupMap::new(StorageKey::QuizzesByStatus),
+
+            active_quizzes_by_id: TreeMap::new(StorageKey::ActiveQuizzesById),
+            active_quizzes_by_prize: TreeMap::new(StorageKey::ActiveQuizzesByPrize),
+            active_quizzes_by_end: TreeMap::new(StorageKey::ActiveQuizzesByEnd),
+            active_quizzes_prizes: LookupMap::new(StorageKey::ActiveQuizzesPrizes),
+
+            entry_fee_payments: LookupMap::new(StorageKey::EntryFeePayments),
+            allowlists: LookupMap::new(StorageKey::Allowlists),
+            waitlists: LookupMap::new(StorageKey::Waitlists),
+            waitlisted_accounts: LookupSet::new(StorageKey::WaitlistedAccounts),
+            released_players: LookupSet::new(StorageKey::ReleasedPlayers),
+            finished_players: LookupMap::new(StorageKey::FinishedPlayers),
+
+            migration: None,
+        }
+    }
+
+    // Only reads the old state, quizzes are moved with migrate_3_batch
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate_3() -> Self {
+        #[derive(BorshDeserialize)]
+        struct OldContract {
+            active_quizzes: UnorderedSet<QuizId>,
+            quizzes: LookupMap<QuizId, QuizOld>,
+
+            questions: LookupMap<QuestionByQuiz, QuestionOld>,
+            question_options: LookupMap<QuestionOptionByQuiz, QuestionOptionOld>,
+            rewards: LookupMap<RewardByQuiz, Reward>,
+
+            games: LookupMap<QuizByUser, GameOld>,
+            players: LookupMap<QuizId, UnorderedSet<AccountId>>,
+            answers: LookupMap<AnswerByQuizByQuestionByUser, AnswerOld>,
+
+            next_quiz_id: QuizId,
+            service_fees_total: LookupMap<TokenAccountId, Balance>,
+
+            quiz_results: LookupMap<QuizResultByQuiz, Vec<AccountId>>,
+            whitelisted_tokens: LookupSet<TokenAccountId>,
+
+            quizzes_by_player_id: LookupMap<AccountId, Vec<QuizId>>,
+            quizzes_by_owner_id: LookupMap<AccountId, Vec<QuizId>>,
+            quizzes_by_sponsor_id: LookupMap<AccountId, Vec<QuizId>>,
+
+            affiliates: LookupMap<QuizId, UnorderedMap<AccountId, u64>>,
+            total_affiliates: UnorderedMap<AccountId, u64>,
+        }
+
+        let old_contract: OldContract = env::state_read().expect("Old state doesn't exist");
+
+        Self {
+            active_quizzes: old_contract.active_quizzes,
+            quizzes: LookupMap::new(StorageKey::QuizzesV2),
+
+            questions: LookupMap::new(StorageKey::QuestionsV1),
+            question_options: LookupMap::new(StorageKey::QuestionOptionsV1),
+            rewards: old_contract.rewards,
+
+            games: LookupMap::new(StorageKey::GamesV1),
+            players: old_contract.players,
+            answers: LookupMap::new(StorageKey::AnswersV1),
+
+            next_quiz_id: old_contract.next_quiz_id,
+            service_fees_total: old_contract.service_fees_total,
+
+            quiz_results: old_contract.quiz_results,
+            whitelisted_tokens: old_contract.whitelisted_tokens,
+
+            quizzes_by_player_id: old_contract.quizzes_by_player_id,
+            quizzes_by_owner_id: old_contract.quizzes_by_owner_id,
+            quizzes_by_sponsor_id: old_contract.quizzes_by_sponsor_id,
+
+            affiliates: old_contract.affiliates,
+            total_affiliates: old_contract.total_affiliates,
+
+            answer_commitments: LookupMap::new(StorageKey::AnswerCommitments),
+            clone_licenses: LookupMap::new(StorageKey::CloneLicenses),
+            quiz_translations: LookupMap::new(StorageKey::QuizTranslations),
+            quizzes_by_tag: LookupMap::new(StorageKey::QuizzesByTag),
+            quizzes_by_language: LookupMap::new(StorageKey::QuizzesByLanguage),
+            quizzes_by_token: LookupMap::new(StorageKey::QuizzesByToken),
+            quizzes_by_status: LookupMap::new(StorageKey::QuizzesByStatus),
+
+            active_quizzes_by_id: TreeMap::new(StorageKey::ActiveQuizzesById),
+            active_quizzes_by_prize: TreeMap::new(StorageKey::ActiveQuizzesByPrize),
+            active_quizzes_by_end: TreeMap::new(StorageKey::ActiveQuizzesByEnd),
+            active_quizzes_prizes: LookupMap::new(StorageKey::ActiveQuizzesPrizes),
+
+            entry_fee_payments: LookupMap::new(StorageKey::EntryFeePayments),
+            allowlists: LookupMap::new(StorageKey::Allowlists),
+            waitlists: LookupMap::new(StorageKey::Waitlists),
+            waitlisted_accounts: LookupSet::new(StorageKey::WaitlistedAccounts),
+            released_players: LookupSet::new(StorageKey::ReleasedPlayers),
+            finished_players: LookupMap::new(StorageKey::FinishedPlayers),
+
+            migration: Some(Migration {
+                quizzes: old_contract.quizzes,
+                questions: old_contract.questions,
+                question_options: old_contract.question_options,
+                games: old_contract.games,
+                answers: old_contract.answers,
+
+                next_quiz_id: old_contract.next_quiz_id,
+                quiz_id: 0,
+                question_id: 0,
+                player_index: 0,
+            }),
+        }
+    }
+
+    // Moves quizzes created before migrate_3 with their questions and games, every step moves a question
+    // with its options, a game of a player with answers or the quiz itself. Quizzes are not available
+    // until all their games are moved. Returns true once the migration is over
+    #[private]
+    pub fn migrate_3_batch(&mut self, limit: u64) -> bool {
+        let mut migration = self.migration.take().expect("Migration is over");
+        let mut steps = 0;
+        while steps < limit && migration.quiz_id < migration.next_quiz_id {
+            let quiz_id = migration.quiz_id;
+            if let Some(quiz) = migration.quizzes.get(&quiz_id) {
+                let question_id = migration.question_id;
+                if question_id < quiz.total_questions {
+                    self.internal_migrate_question(&mut migration, quiz_id, question_id);
+                    migration.question_id += 1;
+                } else if let Some(account_id) = self.players.get(&quiz_id)
+                    .and_then(|players| players.as_vector().get(migration.player_index)) {
+                    self.internal_migrate_game(&mut migration, quiz_id, &quiz, account_id);
+                    migration.player_index += 1;
+                } else {
+                    migration.quizzes.remove(&quiz_id);
+                    self.internal_migrate_quiz(quiz_id, quiz);
+                    migration.quiz_id += 1;
+                    migration.question_id = 0;
+                    migration.player_index = 0;
+                }
+            } else {
+                migration.quiz_id += 1;
+            }
+            steps += 1;
+        }
+
+        let is_over = migration.quiz_id >= migration.next_quiz_id;
+        if !is_over {
+            self.migration = Some(migration);
+        }
+        is_over
+    }
+
+    fn internal_migrate_question(&mut self, migration: &mut Migration, quiz_id: QuizId, question_id: QuestionId) {
+        let question_index = QuizChain::get_question_by_quiz(quiz_id, question_id);
+        if let Some(question) = migration.questions.remove(&question_index) {
+            for question_option_id in 0..question.options_quantity {
+                let question_option_index = QuizChain::get_question_option_by_quiz(quiz_id, question_id, question_option_id);
+                if let Some(question_option) = migration.question_options.remove(&question_option_index) {
+                    self.question_options.insert(&question_option_index,
+                                                 &QuestionOption {
+                                                     content: question_option.content,
+                                                     kind: question_option.kind,
+                                                     column: None,
+                                                 });
+                }
+            }
+
+            self.questions.insert(&question_index,
+                                  &Question {
+                                      kind: question.kind,
+                                      content: question.content,
+                                      hint: question.hint,
+                                      options_quantity: question.options_quantity,
+                                      time_limit: None,
+                                      answer_hash: None,
+                                      text_normalization: None,
+                                      accepted_answer_hashes: None,
+                                      numeric_settings: None,
+                                  });
+        }
+    }
+
+    fn internal_migrate_game(&mut self, migration: &mut Migration, quiz_id: QuizId, quiz: &QuizOld, account_id: AccountId) {
+        let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+        if let Some(game) = migration.games.remove(&game_id) {
+            self.games.insert(&game_id,
+                              &Game {
+                                  answers_quantity: game.answers_quantity,
+                                  current_hash: game.current_hash,
+                                  question_started_at: env::block_timestamp(),
+                                  seed: None,
+                                  correct_answers: 0,
+                                  submitted_at: None,
+                              });
+        }
+
+        for question_id in 0..quiz.total_questions {
+            let answer_index = QuizChain::get_answer_by_quiz_by_question(quiz_id, question_id, account_id.clone());
+            if let Some(answer) = migration.answers.remove(&answer_index) {
+                self.answers.insert(&answer_index,
+                                    &Answer {
+                                        selected_option_ids: answer.selected_option_ids,
+                                        selected_text: answer.selected_text,
+                                        selected_pairs: None,
+                                        timestamp: answer.timestamp,
+                                    });
+            }
+        }
+    }
+
+    fn internal_migrate_quiz(&mut self, quiz_id: QuizId, quiz: QuizOld) {
+        // cancel_quiz didn't store the status of cancelled quizzes, they were only removed from active quizzes.
+        // Quizzes of cancel_funded_quiz kept the Funded status and can't be told apart
+        let status = if quiz.status == QuizStatus::InProgress && !self.active_quizzes.contains(&quiz_id) {
+            QuizStatus::Cancelled
+        } else {
+            quiz.status
+        };
+
+        // Direct quizzes created before this version have a public success hash, so their answers
+        // may be checked offline. They are closed now and anyone may finalize them to refund the owner
+        let ends_at = if status == QuizStatus::InProgress && quiz.finality_type == QuizFinalityType::Direct {
+            Some(env::block_timestamp())
+        } else {
+            None
+        };
+
+        let quiz = Quiz {
+            title: quiz.title,
+            description: quiz.description,
+            language: quiz.language,
+            languages: Vec::new(),
+            tags: Vec::new(),
+            finality_type: quiz.finality_type,
+            owner_id: quiz.owner_id,
+            status,
+            total_questions: quiz.total_questions,
+            questions_per_game: None,
+            shuffle_options: false,
+            available_rewards_ids: quiz.available_rewards_ids,
+            distributed_rewards_ids: quiz.distributed_rewards_ids,
+            secret: quiz.secret,
+            success_hash: quiz.success_hash,
+            success_hash_commitment: None,
+            salt: None,
+            revealed_answers: quiz.revealed_answers.map(|revealed_answers| revealed_answers
+                .into_iter()
+                .map(|revealed_answer| RevealedAnswer {
+                    selected_option_ids: revealed_answer.selected_option_ids,
+                    selected_text: revealed_answer.selected_text,
+                    selected_pairs: None,
+                })
+                .collect()),
+            reveal_started_at: None,
+            sponsor_account_id: quiz.sponsor_account_id,
+            funded_amount: quiz.funded_amount,
+            restart_allowed: quiz.restart_allowed,
+            starts_at: None,
+            ends_at,
+            timestamp: quiz.timestamp,
+            token_account_id: quiz.token_account_id,
+            clone_policy: ClonePolicy::OwnerOnly,
+            activated_at: None,
+            cancel_grace_period: None,
+            scheduled_activation: None,
+            revisable_answers: false,
+            entry_fee: None,
+            entry_fees_quantity: 0,
+            entry_fees_prize_pool: 0,
+            entry_fees_withdrawn: false,
+            eligibility_gates: Vec::new(),
+            invitation_key: None,
+            allowlist_only: false,
+            max_players: None,
+            waitlist_enabled: false,
+            player_timeout: None,
+        };
+
+        self.quizzes.insert(&quiz_id, &quiz);
+        self.internal_index_quiz(quiz_id, &quiz);
+        if quiz.status == QuizStatus::InProgress {
+            self.internal_add_active_quiz(quiz_id, &quiz);
         }
     }
 }
@@ -0,0 +1,56 @@
+use crate::*;
+
+const MAX_NUMERIC_DECIMALS: u8 = 18;
+const MAX_NUMERIC_DIGITS: usize = 36;
+
+#[near_bindgen]
+impl QuizChain {
+    // Numbers are stored as integers scaled by 10^decimals
+    pub(crate) fn parse_numeric_answer(text: &str, decimals: u8) -> i128 {
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text)
+        };
+        let (integer_part, fraction_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+        assert!(!integer_part.is_empty() && integer_part.chars().all(|c| c.is_ascii_digit()), "Illegal number format");
+        assert!(fraction_part.chars().all(|c| c.is_ascii_digit()), "Illegal number format");
+        assert!(fraction_part.len() <= decimals as usize, "Too many decimal places");
+        assert!(integer_part.len() + decimals as usize <= MAX_NUMERIC_DIGITS, "Number is too big");
+
+        let scaled = format!("{}{:0<width$}", integer_part, fraction_part, width = decimals as usize);
+        let value: i128 = scaled.parse().expect("Illegal number format");
+
+        if negative { -value } else { value }
+    }
+
+    pub(crate) fn format_numeric_answer(value: i128, decimals: u8) -> String {
+        let divider = 10i128.pow(decimals as u32);
+        let sign = if value < 0 { "-" } else { "" };
+        if decimals == 0 {
+            format!("{}{}", sign, value.abs())
+        } else {
+            format!("{}{}.{:0>width$}", sign, value.abs() / divider, value.abs() % divider, width = decimals as usize)
+        }
+    }
+
+    // Value of the numeric answer used in the answers hash. All numbers in the range
+    // [offset + bucket * width, offset + (bucket + 1) * width) give the same bucket
+    pub(crate) fn get_numeric_answer_value(numeric_settings: &NumericSettings, value: i128) -> String {
+        let bucket = (value - numeric_settings.bucket_offset as i128).div_euclid(numeric_settings.bucket_width as i128);
+        bucket.to_string()
+    }
+
+    pub(crate) fn assert_valid_numeric_settings(question: &QuestionInput) {
+        if let Some(numeric_settings) = question.numeric_settings.as_ref() {
+            assert_eq!(question.kind, QuestionKind::Numeric, "Only Numeric questions support numeric settings");
+            assert!(numeric_settings.decimals <= MAX_NUMERIC_DECIMALS, "Too many decimal places");
+            assert!(numeric_settings.bucket_width > 0, "Illegal bucket width");
+            // offset outside of the first bucket would disclose the position of the correct range
+            assert!(numeric_settings.bucket_offset < numeric_settings.bucket_width, "Illegal bucket offset");
+        } else {
+            assert_ne!(question.kind, QuestionKind::Numeric, "Numeric settings are missing");
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use near_sdk::json_types::ValidAccountId;
+use near_sdk::json_types::{Base58PublicKey, ValidAccountId};
+use crate::entry_fee::EntryFeeOutput;
 
 use crate::*;
 
-const DAY_IN_NANOSECONDS: Timestamp = 86400000000000;
 const DAYS_BEFORE_CANCEL: u64 = 5;
+const DAYS_BEFORE_DEFAULT_QUIZ_END: u64 = 30;
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -12,21 +13,41 @@ pub struct QuizOutput {
     title: Option<String>,
     description: Option<String>,
     language: Option<String>,
+    languages: Vec<String>,
+    tags: Vec<String>,
     finality_type: QuizFinalityType,
     owner_id: AccountId,
     status: QuizStatus,
     total_questions: u16,
+    questions_per_game: Option<u16>,
+    shuffle_options: bool,
     unclaimed_rewards_ids: Vec<RewardId>,
     secret: Option<String>,
     success_hash: Option<String>,
+    success_hash_commitment: Option<String>,
     questions: Vec<QuestionOutput>,
     available_rewards: Vec<RewardOutput>,
     distributed_rewards: Vec<RewardOutput>,
     revealed_answers: Option<Vec<RevealedAnswer>>,
+    reveal_started_at: Option<Timestamp>,
     timestamp: Option<Timestamp>,
     restart_allowed: bool,
+    starts_at: Option<Timestamp>,
+    ends_at: Option<Timestamp>,
     token_account_id: Option<TokenAccountId>,
-    funded_amount: Option<Balance>
+    funded_amount: Option<Balance>,
+    clone_policy: ClonePolicy,
+    activated_at: Option<Timestamp>,
+    cancel_grace_period: Option<Timestamp>,
+    scheduled_activation_at: Option<Timestamp>,
+    revisable_answers: bool,
+    entry_fee: Option<EntryFeeOutput>,
+    eligibility_gates: Vec<EligibilityGate>,
+    invitation_key: Option<Base58PublicKey>,
+    allowlist_only: bool,
+    max_players: Option<u64>,
+    waitlist_enabled: bool,
+    player_timeout: Option<u64>
 }
 
 // 10 NEAR
@@ -62,21 +83,48 @@ impl QuizChain {
                                 title: None,
                                 description: None,
                                 language: None,
+                                languages: Vec::new(),
+                                tags: Vec::new(),
                                 finality_type: QuizFinalityType::Direct,
                                 owner_id: quiz_owner_id.clone().into(),
                                 status: QuizStatus::Funded,
                                 total_questions: 0,
+                                questions_per_game: None,
+                                shuffle_options: false,
                                 available_rewards_ids: Vec::new(),
                                 distributed_rewards_ids: Vec::new(),
                                 secret: None,
                                 success_hash: None,
+                                success_hash_commitment: None,
+                                salt: None,
                                 revealed_answers: None,
+                                reveal_started_at: None,
                                 sponsor_account_id: Some(sender_id.clone()),
                                 funded_amount: Some(funded_amount),
                                 restart_allowed: false,
+                                starts_at: None,
+                                ends_at: None,
                                 timestamp: Some(env::block_timestamp()),
                                 token_account_id,
+                                clone_policy: ClonePolicy::OwnerOnly,
+                                activated_at: None,
+                                cancel_grace_period: None,
+                                scheduled_activation: None,
+                                revisable_answers: false,
+                                entry_fee: None,
+                                entry_fees_quantity: 0,
+                                entry_fees_prize_pool: 0,
+                                entry_fees_withdrawn: false,
+                                eligibility_gates: Vec::new(),
+                                invitation_key: None,
+                                allowlist_only: false,
+                                max_players: None,
+                                waitlist_enabled: false,
+                                player_timeout: None,
                             });
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            self.internal_index_quiz(quiz_id, &quiz);
+        }
 
         self.add_quiz_for_owner(&quiz_id, quiz_owner_id.into());
         self.add_quiz_for_sponsor(&quiz_id, sender_id);
@@ -96,11 +144,18 @@ impl QuizChain {
                               rewards: Vec<RewardInput>,
                               secret: Option<String>,
                               success_hash: Option<String>,
-                              restart_allowed: bool) -> QuizId {
+                              success_hash_commitment: Option<Hash>,
+                              restart_allowed: bool,
+                              starts_at: Option<Timestamp>,
+                              ends_at: Option<Timestamp>) -> QuizId {
         assert_eq!(questions.len(), all_question_options.len(), "Questions and question options not matched");
         assert!(!questions.is_empty(), "Data not found");
+        QuizChain::assert_valid_success_hash(&finality_type, &success_hash, &success_hash_commitment);
+        QuizChain::assert_valid_quiz_window(starts_at, ends_at);
         if let Some(quiz) = self.quizzes.get(&quiz_id) {
-            assert_eq!(quiz.status, QuizStatus::Funded);
+            QuizChain::assert_current_user(&quiz.owner_id);
+            QuizChain::assert_quiz_status_transition(quiz.status, QuizStatus::Locked);
+            self.internal_unindex_quiz(quiz_id, &quiz);
             let mut unclaimed_rewards_ids = Vec::new();
             let mut rewards_total: Balance = 0;
 
@@ -128,7 +183,10 @@ impl QuizChain {
 
             let mut question_id: QuestionId = 0;
             for question in &questions {
+                QuizChain::assert_valid_text_answer_settings(question);
+                QuizChain::assert_valid_numeric_settings(question);
                 if let Some(question_options) = all_question_options.get(question_id as usize) {
+                    QuizChain::assert_valid_question_options(question.kind, question_options);
                     let mut question_option_id: QuestionOptionId = 0;
                     for question_option in question_options {
                         self.question_options.insert(
@@ -147,6 +205,11 @@ impl QuizChain {
                         hint: question.hint.clone(),
                         options_quantity,
                         kind: question.kind,
+                        time_limit: question.time_limit,
+                        answer_hash: question.answer_hash.clone(),
+                        text_normalization: question.text_normalization.clone(),
+                        accepted_answer_hashes: question.accepted_answer_hashes.clone(),
+                        numeric_settings: question.numeric_settings.clone(),
                     });
 
                 question_id += 1;
@@ -156,26 +219,50 @@ impl QuizChain {
                 title: Some(title),
                 description,
                 language,
+                languages: Vec::new(),
+                tags: Vec::new(),
                 finality_type,
-                owner_id: env::predecessor_account_id(),
+                owner_id: quiz.owner_id.clone(),
                 status: QuizStatus::Locked,
                 total_questions,
+                questions_per_game: None,
+                shuffle_options: false,
                 available_rewards_ids: unclaimed_rewards_ids,
                 distributed_rewards_ids: Vec::new(),
                 secret: secret.clone(),
                 success_hash: success_hash.clone(),
+                success_hash_commitment: success_hash_commitment.clone(),
+                salt: None,
                 revealed_answers: None,
-                sponsor_account_id: None,
-                funded_amount: None,
+                reveal_started_at: None,
+                sponsor_account_id: quiz.sponsor_account_id,
+                funded_amount: quiz.funded_amount,
                 restart_allowed,
+                starts_at,
+                ends_at,
                 timestamp: Some(env::block_timestamp()),
                 token_account_id: quiz.token_account_id,
+                clone_policy: ClonePolicy::OwnerOnly,
+                activated_at: None,
+                cancel_grace_period: None,
+                scheduled_activation: None,
+                revisable_answers: false,
+                entry_fee: None,
+                entry_fees_quantity: 0,
+                entry_fees_prize_pool: 0,
+                entry_fees_withdrawn: false,
+                eligibility_gates: Vec::new(),
+                invitation_key: None,
+                allowlist_only: false,
+                max_players: None,
+                waitlist_enabled: false,
+                player_timeout: None,
             };
             self.quizzes.insert(&quiz_id, &quiz);
-            self.add_quiz_for_owner(&quiz_id, env::predecessor_account_id());
+            self.internal_index_quiz(quiz_id, &quiz);
 
             if let Some(secret_unwrapped) = secret {
-                self.activate_quiz(quiz_id, secret_unwrapped, success_hash)
+                self.activate_quiz(quiz_id, secret_unwrapped, success_hash, success_hash_commitment)
             }
 
             quiz_id
@@ -213,7 +300,7 @@ impl QuizChain {
         assert!(from_index <= quizzes_ids_qty, "Illegal from_index");
         let limit_id = std::cmp::min(from_index + limit, quizzes_ids_qty);
         for quiz_index in from_index..limit_id {
-            if let Some(quiz) = self.get_quiz(quizzes_ids[quiz_index]) {
+            if let Some(quiz) = self.get_quiz(quizzes_ids[quiz_index], None) {
                 quizzes.push(quiz);
             }
         }
@@ -299,7 +386,10 @@ impl QuizChain {
                        rewards: Vec<RewardInput>,
                        secret: Option<String>,
                        success_hash: Option<String>,
+                       success_hash_commitment: Option<Hash>,
                        restart_allowed: bool,
+                       starts_at: Option<Timestamp>,
+                       ends_at: Option<Timestamp>,
                        token_account_id: Option<TokenAccountId>) -> QuizId {
         let deposit = env::attached_deposit();
         let owner_id = env::predecessor_account_id();
@@ -314,12 +404,15 @@ impl QuizChain {
                                                 rewards,
                                                 secret.clone(),
                                                 success_hash.clone(),
+                                                success_hash_commitment.clone(),
                                                 restart_allowed,
+                                                starts_at,
+                                                ends_at,
                                                 deposit,
                                                 token_account_id);
 
         if let Some(secret_unwrapped) = secret {
-            self.activate_quiz(quiz_id, secret_unwrapped, success_hash);
+            self.activate_quiz(quiz_id, secret_unwrapped, success_hash, success_hash_commitment);
         }
 
         quiz_id
@@ -336,11 +429,16 @@ impl QuizChain {
                                 rewards: Vec<RewardInput>,
                                 secret: Option<String>,
                                 success_hash: Option<String>,
+                                success_hash_commitment: Option<Hash>,
                                 restart_allowed: bool,
+                                starts_at: Option<Timestamp>,
+                                ends_at: Option<Timestamp>,
                                 deposit: Balance,
                                 token_account_id: Option<TokenAccountId>) -> QuizId {
         assert_eq!(questions.len(), all_question_options.len(), "Questions and question options not matched");
         assert!(!questions.is_empty(), "Data not found");
+        QuizChain::assert_valid_success_hash(&finality_type, &success_hash, &success_hash_commitment);
+        QuizChain::assert_valid_quiz_window(starts_at, ends_at);
 
         let quiz_id = self.next_quiz_id;
 
@@ -379,7 +477,10 @@ impl QuizChain {
 
         let mut question_id: QuestionId = 0;
         for question in &questions {
+            QuizChain::assert_valid_text_answer_settings(question);
+            QuizChain::assert_valid_numeric_settings(question);
             if let Some(question_options) = all_question_options.get(question_id as usize) {
+                QuizChain::assert_valid_question_options(question.kind, question_options);
                 let mut question_option_id: QuestionOptionId = 0;
                 for question_option in question_options {
                     self.question_options.insert(
@@ -397,7 +498,12 @@ impl QuizChain {
                     content: question.content.clone(),
                     hint: question.hint.clone(),
                     options_quantity,
-                    kind: question.kind
+                    kind: question.kind,
+                    time_limit: question.time_limit,
+                    answer_hash: question.answer_hash.clone(),
+                    text_normalization: question.text_normalization.clone(),
+                    accepted_answer_hashes: question.accepted_answer_hashes.clone(),
+                    numeric_settings: question.numeric_settings.clone(),
                 });
 
             question_id += 1;
@@ -407,22 +513,47 @@ impl QuizChain {
             title: Some(title),
             description,
             language,
+            languages: Vec::new(),
+            tags: Vec::new(),
             finality_type,
             owner_id: owner_id.clone(),
             status: QuizStatus::Locked,
             total_questions,
+            questions_per_game: None,
+            shuffle_options: false,
             available_rewards_ids: unclaimed_rewards_ids,
             distributed_rewards_ids: Vec::new(),
             secret,
             success_hash,
+            success_hash_commitment,
+            salt: None,
             revealed_answers: None,
+            reveal_started_at: None,
             sponsor_account_id: None,
             funded_amount: None,
             restart_allowed,
+            starts_at,
+            ends_at,
             timestamp: Some(env::block_timestamp()),
             token_account_id,
+            clone_policy: ClonePolicy::OwnerOnly,
+            activated_at: None,
+            cancel_grace_period: None,
+            scheduled_activation: None,
+            revisable_answers: false,
+            entry_fee: None,
+            entry_fees_quantity: 0,
+            entry_fees_prize_pool: 0,
+            entry_fees_withdrawn: false,
+            eligibility_gates: Vec::new(),
+            invitation_key: None,
+            allowlist_only: false,
+            max_players: None,
+            waitlist_enabled: false,
+            player_timeout: None,
         };
         self.quizzes.insert(&quiz_id, &quiz);
+        self.internal_index_quiz(quiz_id, &quiz);
 
         self.add_quiz_for_owner(&quiz_id, owner_id);
 
@@ -440,11 +571,17 @@ impl QuizChain {
                                 rewards: Vec<RewardInput>,
                                 secret: Secret,
                                 success_hash: Option<Hash>,
+                                success_hash_commitment: Option<Hash>,
                                 restart_allowed: bool,
+                                starts_at: Option<Timestamp>,
+                                ends_at: Option<Timestamp>,
                                 deposit: Balance,
                                 token_account_id: Option<TokenAccountId>) -> QuizId {
         assert_eq!(questions.len(), all_question_options.len(), "Questions and question options not matched");
         assert!(!questions.is_empty(), "Data not found");
+        QuizChain::assert_valid_success_hash(&finality_type, &success_hash, &success_hash_commitment);
+        QuizChain::assert_valid_quiz_window(starts_at, ends_at);
+        QuizChain::assert_success_hash_commitment_for_activation(&finality_type, &success_hash_commitment);
 
         let quiz_id = self.next_quiz_id;
 
@@ -483,7 +620,10 @@ impl QuizChain {
 
         let mut question_id: QuestionId = 0;
         for question in &questions {
+            QuizChain::assert_valid_text_answer_settings(question);
+            QuizChain::assert_valid_numeric_settings(question);
             if let Some(question_options) = all_question_options.get(question_id as usize) {
+                QuizChain::assert_valid_question_options(question.kind, question_options);
                 let mut question_option_id: QuestionOptionId = 0;
                 for question_option in question_options {
                     self.question_options.insert(
@@ -501,7 +641,12 @@ impl QuizChain {
                     content: question.content.clone(),
                     hint: question.hint.clone(),
                     options_quantity,
-                    kind: question.kind
+                    kind: question.kind,
+                    time_limit: question.time_limit,
+                    answer_hash: question.answer_hash.clone(),
+                    text_normalization: question.text_normalization.clone(),
+                    accepted_answer_hashes: question.accepted_answer_hashes.clone(),
+                    numeric_settings: question.numeric_settings.clone(),
                 });
 
             question_id += 1;
@@ -511,78 +656,201 @@ impl QuizChain {
             title: Some(title),
             description,
             language,
+            languages: Vec::new(),
+            tags: Vec::new(),
             finality_type,
             owner_id: owner_id.clone(),
             status: QuizStatus::InProgress,
             total_questions,
+            questions_per_game: None,
+            shuffle_options: false,
             available_rewards_ids: unclaimed_rewards_ids,
             distributed_rewards_ids: Vec::new(),
             secret: Some(secret),
             success_hash,
+            success_hash_commitment,
+            salt: None,
             revealed_answers: None,
+            reveal_started_at: None,
             sponsor_account_id: None,
             funded_amount: None,
             restart_allowed,
+            starts_at,
+            ends_at,
             timestamp: Some(env::block_timestamp()),
             token_account_id,
+            clone_policy: ClonePolicy::OwnerOnly,
+            activated_at: Some(env::block_timestamp()),
+            cancel_grace_period: None,
+            scheduled_activation: None,
+            revisable_answers: false,
+            entry_fee: None,
+            entry_fees_quantity: 0,
+            entry_fees_prize_pool: 0,
+            entry_fees_withdrawn: false,
+            eligibility_gates: Vec::new(),
+            invitation_key: None,
+            allowlist_only: false,
+            max_players: None,
+            waitlist_enabled: false,
+            player_timeout: None,
         };
         self.quizzes.insert(&quiz_id, &quiz);
+        self.internal_index_quiz(quiz_id, &quiz);
 
-        self.active_quizzes.insert(&quiz_id);
+        self.internal_add_active_quiz(quiz_id, &quiz);
 
         self.add_quiz_for_owner(&quiz_id, owner_id);
 
         quiz_id
     }
 
-    pub fn activate_quiz(&mut self, quiz_id: QuizId, secret: Secret, success_hash: Option<Hash>) {
-        self.activate_quiz_internal(env::predecessor_account_id(), quiz_id, secret, success_hash);
+    pub fn activate_quiz(&mut self, quiz_id: QuizId, secret: Secret, success_hash: Option<Hash>, success_hash_commitment: Option<Hash>) {
+        self.activate_quiz_internal(env::predecessor_account_id(), quiz_id, secret, success_hash, success_hash_commitment);
     }
 
-    pub(crate) fn activate_quiz_internal(&mut self, quiz_owner_id: AccountId, quiz_id: QuizId, secret: Secret, success_hash: Option<Hash>) {
+    pub(crate) fn activate_quiz_internal(&mut self, quiz_owner_id: AccountId, quiz_id: QuizId, secret: Secret,
+                                         success_hash: Option<Hash>, success_hash_commitment: Option<Hash>) {
         if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
             assert_eq!(quiz.owner_id, quiz_owner_id, "Not a quiz owner");
-            assert_eq!(quiz.status, QuizStatus::Locked, "Quiz was already unlocked");
+            self.assert_quiz_ready_for_activation(quiz_id, &quiz, &success_hash, &success_hash_commitment);
 
             quiz.secret = Some(secret);
-            quiz.status = QuizStatus::InProgress;
             quiz.success_hash = success_hash;
+            quiz.success_hash_commitment = success_hash_commitment;
+            quiz.activated_at = Some(env::block_timestamp());
+            quiz.scheduled_activation = None;
+            self.internal_set_quiz_status(quiz_id, &mut quiz, QuizStatus::InProgress);
             self.quizzes.insert(&quiz_id, &quiz);
-            self.active_quizzes.insert(&quiz_id);
         }
     }
 
-    #[private]
+    pub(crate) fn assert_quiz_ready_for_activation(&self, quiz_id: QuizId, quiz: &Quiz, success_hash: &Option<Hash>, success_hash_commitment: &Option<Hash>) {
+        assert_eq!(quiz.status, QuizStatus::Locked, "Quiz was already unlocked");
+        QuizChain::assert_valid_success_hash(&quiz.finality_type, success_hash, success_hash_commitment);
+        self.assert_valid_translations(quiz_id, quiz);
+        if self.has_accepted_answers(quiz_id, quiz) {
+            // accepted answers are salted with the secret of question pools or the salt of the success hash commitment
+            assert!(QuizChain::is_success_hash_committed(&quiz.finality_type), "Accepted answers are not supported for this quiz");
+        }
+        if let Some(questions_per_game) = quiz.questions_per_game {
+            // answers of question pools are verified by hashes of every question, the success hash is not used
+            assert!(questions_per_game <= quiz.total_questions, "Question pool is too small");
+            assert!(success_hash_commitment.is_none(), "Question pools are verified on every answer");
+            for question_id in 0..quiz.total_questions {
+                let question = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)).expect("Question not found");
+                assert!(question.answer_hash.is_some(), "Answer hash of question {} is missing", question_id);
+            }
+        } else {
+            QuizChain::assert_success_hash_commitment_for_activation(&quiz.finality_type, success_hash_commitment);
+        }
+    }
+
+    // Direct and CommitReveal quizzes never store a plain success hash before the finish, otherwise
+    // all option combinations may be hashed offline and compared with it
+    pub(crate) fn assert_valid_success_hash(finality_type: &QuizFinalityType, success_hash: &Option<Hash>, success_hash_commitment: &Option<Hash>) {
+        if QuizChain::is_success_hash_committed(finality_type) {
+            assert!(success_hash.is_none(), "Success hash of this quiz is revealed on finish, provide success_hash_commitment instead");
+        }
+        if let Some(success_hash_commitment_unwrapped) = success_hash_commitment {
+            assert_eq!(success_hash_commitment_unwrapped.chars().count(), 64, "Illegal hash length");
+        }
+    }
+
+    pub(crate) fn assert_success_hash_commitment_for_activation(finality_type: &QuizFinalityType, success_hash_commitment: &Option<Hash>) {
+        if QuizChain::is_success_hash_committed(finality_type) {
+            assert!(success_hash_commitment.is_some(), "Success hash commitment is missing");
+        }
+    }
+
+    pub(crate) fn is_success_hash_committed(finality_type: &QuizFinalityType) -> bool {
+        [QuizFinalityType::Direct, QuizFinalityType::CommitReveal].contains(finality_type)
+    }
+
+    pub(crate) fn assert_valid_quiz_window(starts_at: Option<Timestamp>, ends_at: Option<Timestamp>) {
+        if let Some(ends_at_unwrapped) = ends_at {
+            assert!(ends_at_unwrapped > env::block_timestamp(), "Illegal end of the quiz");
+            if let Some(starts_at_unwrapped) = starts_at {
+                assert!(starts_at_unwrapped < ends_at_unwrapped, "Quiz should start before the end");
+            }
+        }
+    }
+
+    pub(crate) fn assert_quiz_is_open(quiz: &Quiz) {
+        let now = env::block_timestamp();
+        if let Some(starts_at) = quiz.starts_at {
+            assert!(now >= starts_at, "Quiz is not started yet");
+        }
+        if let Some(ends_at) = quiz.ends_at {
+            assert!(now <= ends_at, "Quiz is already over");
+        }
+        assert!(quiz.reveal_started_at.is_none(), "Success hash was already revealed");
+    }
+
+    pub(crate) fn get_success_hash_commitment(success_hash: &Hash, salt: &str) -> Hash {
+        QuizChain::get_hash(format!("{}{}", success_hash, salt))
+    }
+
+    // Owners may cancel Locked quizzes at any time and quizzes in progress after the grace period.
+    // Rewards which were already won stay claimable by winners, games in progress can't be continued.
+    // Remaining rewards of funded quizzes return to the sponsor
     pub fn cancel_quiz(&mut self, quiz_id: QuizId) -> PromiseOrValue<bool> {
         if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
             assert!([QuizStatus::InProgress, QuizStatus::Locked].contains(&quiz.status), "Quiz is not available to cancel");
-            if let Some(timestamp) = quiz.timestamp {
-                assert!(env::block_timestamp() - timestamp > DAY_IN_NANOSECONDS * DAYS_BEFORE_CANCEL, "To early to cancel");
+            if env::predecessor_account_id() == env::current_account_id() {
+                if let Some(timestamp) = quiz.timestamp {
+                    assert!(env::block_timestamp() - timestamp > DAY_IN_NANOSECONDS * DAYS_BEFORE_CANCEL, "To early to cancel");
+                } else {
+                    return PromiseOrValue::Value(false);
+                }
+            } else {
+                QuizChain::assert_current_user(&quiz.owner_id);
+                if quiz.status == QuizStatus::InProgress {
+                    self.assert_quiz_cancel_allowed_for_owner(quiz_id, &quiz);
+                }
+            }
 
-                quiz.status = QuizStatus::Finished;
-                self.active_quizzes.remove(&quiz_id);
+            let available_rewards = self.get_remaining_reward(quiz_id, &quiz);
 
-                let available_rewards = self.get_available_rewards(quiz_id);
-                return PromiseOrValue::Promise(self.withdraw_available_rewards(available_rewards.0, quiz.owner_id, quiz.token_account_id));
-            }
+            quiz.available_rewards_ids = [].to_vec();
+            self.internal_set_quiz_status(quiz_id, &mut quiz, QuizStatus::Cancelled);
+            self.quizzes.insert(&quiz_id, &quiz);
+
+            let refund_account_id = quiz.sponsor_account_id.unwrap_or(quiz.owner_id);
+            return PromiseOrValue::Promise(self.withdraw_available_rewards(available_rewards, refund_account_id, quiz.token_account_id));
         }
         PromiseOrValue::Value(false)
     }
 
+    fn assert_quiz_cancel_allowed_for_owner(&self, quiz_id: QuizId, quiz: &Quiz) {
+        let activated_at = quiz.activated_at.or(quiz.timestamp).unwrap_or(0);
+        let cancel_grace_period = quiz.cancel_grace_period.unwrap_or(DAY_IN_NANOSECONDS * DAYS_BEFORE_CANCEL);
+        assert!(env::block_timestamp() - activated_at > cancel_grace_period, "To early to cancel");
+
+        // winners of these quizzes are known only after the reveal, so games of players can't be dropped
+        assert!(quiz.reveal_started_at.is_none(), "Answers are being revealed");
+        assert!(self.finished_players.get(&quiz_id).map(|finished_players| finished_players.is_empty()).unwrap_or(true),
+                "Players are awaiting the reveal");
+        if quiz.finality_type == QuizFinalityType::DelayedReveal {
+            assert!(self.players.get(&quiz_id).map(|players| players.is_empty()).unwrap_or(true), "Quiz already has players");
+        }
+    }
+
     pub fn cancel_funded_quiz(&mut self, quiz_id: QuizId) -> PromiseOrValue<bool> {
         if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
-            if let Some(sponsor_account_id) = quiz.sponsor_account_id {
+            if let Some(sponsor_account_id) = quiz.sponsor_account_id.clone() {
                 assert_eq!(sponsor_account_id, env::predecessor_account_id(), "No access. Only sponsors may cancel inactive quizzes");
                 assert_eq!(quiz.status, QuizStatus::Funded, "Quiz was updated");
                 if let Some(timestamp) = quiz.timestamp {
                     assert!(env::block_timestamp() - timestamp > DAY_IN_NANOSECONDS * DAYS_BEFORE_CANCEL, "To early to cancel");
 
-                    quiz.status = QuizStatus::Finished;
-                    self.active_quizzes.remove(&quiz_id);
+                    let available_rewards = self.get_remaining_reward(quiz_id, &quiz);
 
-                    let available_rewards = self.get_available_rewards(quiz_id);
+                    quiz.available_rewards_ids = [].to_vec();
+                    self.internal_set_quiz_status(quiz_id, &mut quiz, QuizStatus::Cancelled);
+                    self.quizzes.insert(&quiz_id, &quiz);
 
-                    return PromiseOrValue::Promise(self.withdraw_available_rewards(available_rewards.0, sponsor_account_id, quiz.token_account_id))
+                    return PromiseOrValue::Promise(self.withdraw_available_rewards(available_rewards, sponsor_account_id, quiz.token_account_id))
                 }
             }
         }
@@ -596,44 +864,64 @@ impl QuizChain {
         if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
             QuizChain::assert_current_user(&quiz.owner_id);
             assert!(quiz.revealed_answers.is_none(), "Answers were already revealed");
-            assert_eq!(quiz.status, QuizStatus::Finished, "Quiz is not finished");
+            assert!([QuizStatus::Finished, QuizStatus::Expired].contains(&quiz.status), "Quiz is not finished");
             assert_eq!(quiz.total_questions, revealed_answers.len() as u16, "Illegal answers quantity");
 
-            let mut hash = QuizChain::get_hash(quiz.secret.clone().unwrap());
-            let questions = self.get_questions_by_quiz(quiz_id);
-
-            let mut question_id: QuestionId = 0;
-            for answer in &revealed_answers {
-                let answer_value = if answer.selected_option_ids.is_some(){
-                    let question: &QuestionOutput = questions.get(question_id as usize).unwrap();
-                    let options_quantity = question.question_options.len() as QuestionId;
-                    let options = self.get_question_options_by_question_id(quiz_id, question_id, options_quantity);
-
-                    let mut answer_string: String = "".to_string();
-                    let mut option_ids: Vec<QuestionOptionId> = revealed_answers[question_id as usize].selected_option_ids.clone().unwrap();
-                    option_ids.sort_unstable();
-                    for option_id in &option_ids {
-                        answer_string = format!("{}{}", answer_string, options[*option_id as usize].content.to_lowercase());
-                    }
-                    answer_string
+            let secret = quiz.secret.clone().unwrap();
+            if quiz.questions_per_game.is_some() {
+                for (question_id, answer) in revealed_answers.iter().enumerate() {
+                    let question_id = question_id as QuestionId;
+                    let question = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)).expect("Question not found");
+                    let answer_value = self.get_revealed_answer_value(quiz_id, question_id, Some(&secret), answer);
+                    assert_eq!(Some(QuizChain::get_answer_hash(&secret, &answer_value)), question.answer_hash,
+                               "Provided answers are not valid");
                 }
-                else {
-                    answer.selected_text.clone().unwrap().to_lowercase()
-                };
-
-                hash = QuizChain::get_hash(format!("{}{}", hash, answer_value));
+            } else {
+                let hash = self.get_revealed_answers_hash(quiz_id, secret, quiz.salt.as_deref(), &revealed_answers);
 
-                question_id += 1;
+                assert_eq!(hash, quiz.success_hash.clone().unwrap(), "Provided answers are not valid");
             }
 
-            assert_eq!(hash, quiz.success_hash.clone().unwrap(), "Provided answers are not valid");
-
             quiz.revealed_answers = Some(revealed_answers);
             self.quizzes.insert(&quiz_id, &quiz);
             log!("Provided answers are valid");
         }
     }
 
+    pub(crate) fn get_revealed_answers_hash(&self, quiz_id: QuizId, secret: Secret, salt: Option<&str>, revealed_answers: &[RevealedAnswer]) -> Hash {
+        let mut hash = QuizChain::get_hash(secret);
+
+        for (question_id, answer) in revealed_answers.iter().enumerate() {
+            let answer_value = self.get_revealed_answer_value(quiz_id, question_id as QuestionId, salt, answer);
+            hash = QuizChain::get_hash(format!("{}{}", hash, answer_value));
+        }
+
+        hash
+    }
+
+    pub(crate) fn get_revealed_answer_value(&self, quiz_id: QuizId, question_id: QuestionId, salt: Option<&str>, answer: &RevealedAnswer) -> String {
+        let question = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)).expect("Question not found");
+        if question.kind == QuestionKind::Matching {
+            let (_, answer_value) = self.get_matching_answer(quiz_id, question_id, &question, answer.selected_pairs.clone().expect("Answer pairs are missing"));
+            answer_value
+        }
+        else if let Some(selected_option_ids) = answer.selected_option_ids.as_ref() {
+            let option_ids = QuizChain::get_canonical_option_ids(&question, selected_option_ids.clone());
+
+            let mut answer_string: String = "".to_string();
+            for option_id in &option_ids {
+                let option = self.question_options.get(&QuizChain::get_question_option_by_quiz(quiz_id, question_id, *option_id))
+                    .expect("Question option not found");
+                answer_string = format!("{}{}", answer_string, option.content.to_lowercase());
+            }
+            answer_string
+        }
+        else {
+            let (_, answer_value) = QuizChain::get_text_answer(salt, &question, &answer.selected_text.clone().unwrap());
+            answer_value
+        }
+    }
+
     #[payable]
     pub fn reveal_final_hash(&mut self, quiz_id: QuizId, hash: Hash) -> PromiseOrValue<bool> {
         assert_one_yocto();
@@ -646,42 +934,155 @@ impl QuizChain {
             assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not in Progress");
 
             let winners: Vec<AccountId> = self.quiz_results.get(&QuizResultByQuiz { quiz_id, hash: hash.clone() }).unwrap_or([].to_vec());
-            let winners_qty = winners.len() as u16;
-            let total_rewards_qty = quiz.available_rewards_ids.len();
-
-            let mut unspent_rewards: Balance = 0;
-
-            for reward_id in 0..total_rewards_qty as RewardId {
-                let reward_index = QuizChain::get_reward_by_quiz(quiz_id, reward_id);
-                if let Some(mut reward) = self.rewards.get(&reward_index) {
-                    if reward_id < winners_qty {
-                        assert!(reward.winner_account_id.is_none(), "Reward already distributed");
-                        let winner_account_id = winners[reward_id as usize].clone();
-                        reward.winner_account_id = Some(winner_account_id);
-                        self.rewards.insert(&reward_index, &reward);
-                        quiz.distributed_rewards_ids.push(reward_id);
-                    } else {
-                        unspent_rewards += reward.amount;
-                    }
+
+            quiz.success_hash = Some(hash);
+            self.internal_distribute_rewards(quiz_id, quiz, winners)
+        } else {
+            PromiseOrValue::Value(false)
+        }
+    }
+
+    // Reveals a success hash committed on activation, anyone who knows the salt may call it.
+    // Direct quiz is finished and rewarded to the players who completed it with this hash first.
+    // CommitReveal quiz stops accepting commitments and opens the answers reveal period, games of
+    // quizzes with accepted answers are verified with the revealed salt during the same period
+    pub fn reveal_success_hash(&mut self, quiz_id: QuizId, success_hash: Hash, salt: String) -> PromiseOrValue<bool> {
+        assert_eq!(success_hash.chars().count(), 64, "Illegal hash length");
+
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+            assert!(QuizChain::is_success_hash_committed(&quiz.finality_type), "Hash reveal is not supported");
+            assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not in Progress");
+            assert!(quiz.reveal_started_at.is_none(), "Success hash was already revealed");
+
+            if let Some(success_hash_commitment) = quiz.success_hash_commitment.clone() {
+                assert_eq!(QuizChain::get_success_hash_commitment(&success_hash, &salt), success_hash_commitment,
+                           "Success hash doesn't match the commitment");
+            } else {
+                panic!("Success hash commitment not found");
+            }
+
+            quiz.salt = Some(salt);
+            if quiz.finality_type == QuizFinalityType::CommitReveal || self.is_verified_after_reveal(quiz_id, &quiz) {
+                quiz.success_hash = Some(success_hash);
+                quiz.reveal_started_at = Some(env::block_timestamp());
+                self.quizzes.insert(&quiz_id, &quiz);
+                return PromiseOrValue::Value(true);
+            }
+
+            let winners: Vec<AccountId> = self.quiz_results.get(&QuizResultByQuiz { quiz_id, hash: success_hash.clone() }).unwrap_or([].to_vec());
+
+            quiz.success_hash = Some(success_hash);
+            self.internal_distribute_rewards(quiz_id, quiz, winners)
+        } else {
+            PromiseOrValue::Value(false)
+        }
+    }
+
+    pub(crate) fn internal_distribute_rewards(&mut self, quiz_id: QuizId, mut quiz: Quiz, winners: Vec<AccountId>) -> PromiseOrValue<bool> {
+        let winners_qty = winners.len() as u16;
+        let total_rewards_qty = quiz.available_rewards_ids.len();
+
+        let mut unspent_rewards: Balance = 0;
+
+        for reward_id in 0..total_rewards_qty as RewardId {
+            let reward_index = QuizChain::get_reward_by_quiz(quiz_id, reward_id);
+            if let Some(mut reward) = self.rewards.get(&reward_index) {
+                if reward_id < winners_qty {
+                    assert!(reward.winner_account_id.is_none(), "Reward already distributed");
+                    let winner_account_id = winners[reward_id as usize].clone();
+                    reward.winner_account_id = Some(winner_account_id);
+                    QuizChain::internal_add_entry_fees_to_reward(&mut quiz, &mut reward);
+                    self.rewards.insert(&reward_index, &reward);
+                    quiz.distributed_rewards_ids.push(reward_id);
+                } else {
+                    unspent_rewards += reward.amount;
                 }
             }
+        }
 
-            quiz.available_rewards_ids = [].to_vec();
-            quiz.status = QuizStatus::Finished;
-            quiz.success_hash = Some(hash);
-            self.active_quizzes.remove(&quiz_id);
-            self.quizzes.insert(&quiz_id, &quiz);
+        quiz.available_rewards_ids = [].to_vec();
+        self.internal_set_quiz_status(quiz_id, &mut quiz, QuizStatus::Finished);
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        if unspent_rewards > 0 {
+            PromiseOrValue::Promise(self.withdraw_available_rewards(unspent_rewards, quiz.owner_id, quiz.token_account_id))
+        } else {
+            PromiseOrValue::Value(true)
+        }
+    }
+
+    // Finishes the quiz once nobody is able to win it anymore. CommitReveal quizzes are rewarded after
+    // the answers reveal period, other quizzes are closed after ends_at and a day more if the owner
+    // still has to reveal the success hash or the final hash. If the hash wasn't revealed in time, rewards
+    // go to the players who finished the game first instead. Unspent rewards return to the owner.
+    // Quizzes awaiting the hash reveal without ends_at end 30 days after the activation
+    pub fn finalize_quiz(&mut self, quiz_id: QuizId) -> PromiseOrValue<bool> {
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+            assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not in Progress");
+
+            if let Some(reveal_started_at) = quiz.reveal_started_at {
+                assert!(env::block_timestamp() > QuizChain::get_answers_reveal_end(reveal_started_at), "Reveal period is not over");
+
+                let winners = if quiz.finality_type == QuizFinalityType::CommitReveal {
+                    self.get_commit_reveal_winners(quiz_id, &quiz)
+                } else {
+                    self.get_verified_winners(quiz_id, &quiz)
+                };
+                return self.internal_distribute_rewards(quiz_id, quiz, winners);
+            }
+
+            if let Some(ends_at) = QuizChain::get_quiz_end(&quiz) {
+                let awaiting_hash_reveal = QuizChain::is_awaiting_hash_reveal(&quiz);
+                let finalize_at = if awaiting_hash_reveal { ends_at + DAY_IN_NANOSECONDS } else { ends_at };
+                assert!(env::block_timestamp() > finalize_at, "Too early to finalize");
 
-            if unspent_rewards > 0 {
-                PromiseOrValue::Promise(self.withdraw_available_rewards(unspent_rewards, quiz.owner_id, quiz.token_account_id))
+                if awaiting_hash_reveal {
+                    let winners = self.get_finished_players(quiz_id, quiz.available_rewards_ids.len());
+                    return self.internal_distribute_rewards(quiz_id, quiz, winners);
+                }
+
+                let available_rewards = self.get_available_rewards(quiz_id);
+
+                // Quizzes closed with unclaimed rewards are Expired
+                let status = if available_rewards.0 > 0 { QuizStatus::Expired } else { QuizStatus::Finished };
+                quiz.available_rewards_ids = [].to_vec();
+                self.internal_set_quiz_status(quiz_id, &mut quiz, status);
+                self.quizzes.insert(&quiz_id, &quiz);
+
+                if available_rewards.0 > 0 {
+                    PromiseOrValue::Promise(self.withdraw_available_rewards(available_rewards.0, quiz.owner_id, quiz.token_account_id))
+                } else {
+                    PromiseOrValue::Value(true)
+                }
             } else {
-                PromiseOrValue::Value(true)
+                panic!("Quiz has no end");
             }
         } else {
             PromiseOrValue::Value(false)
         }
     }
 
+    fn is_awaiting_hash_reveal(quiz: &Quiz) -> bool {
+        quiz.finality_type == QuizFinalityType::DelayedReveal || quiz.success_hash_commitment.is_some()
+    }
+
+    // Rewards of a quiz awaiting the hash reveal can't be locked forever if the owner never reveals it
+    fn get_quiz_end(quiz: &Quiz) -> Option<Timestamp> {
+        if quiz.ends_at.is_none() && QuizChain::is_awaiting_hash_reveal(quiz) {
+            Some(quiz.activated_at.or(quiz.timestamp).unwrap_or(0) + DAY_IN_NANOSECONDS * DAYS_BEFORE_DEFAULT_QUIZ_END)
+        } else {
+            quiz.ends_at
+        }
+    }
+
+    fn get_finished_players(&self, quiz_id: QuizId, limit: usize) -> Vec<AccountId> {
+        if let Some(finished_players) = self.finished_players.get(&quiz_id) {
+            finished_players.iter().take(limit).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
     pub fn get_users_with_final_hash(&self, quiz_id: QuizId, hash: Hash) -> Option<Vec<AccountId>> {
         return self.quiz_results.get(&QuizResultByQuiz { quiz_id, hash })
     }
@@ -691,12 +1092,7 @@ impl QuizChain {
         if let Some(quiz) = self.quizzes.get(&quiz_id) {
             assert_eq!(quiz.status, QuizStatus::InProgress, "Quiz is not in Progress");
 
-            for reward_id in 0..quiz.available_rewards_ids.len() as RewardId {
-                let reward_index = QuizChain::get_reward_by_quiz(quiz_id, reward_id);
-                if let Some(reward) = self.rewards.get(&reward_index) {
-                    unspent_rewards += reward.amount;
-                }
-            }
+            unspent_rewards = self.get_remaining_reward(quiz_id, &quiz);
         }
         unspent_rewards.into()
     }
@@ -717,28 +1113,56 @@ impl QuizChain {
         assert_eq!(*owner_id, env::predecessor_account_id(), "No access");
     }
 
-    pub fn get_quiz(&self, quiz_id: QuizId) -> Option<QuizOutput> {
+    pub fn get_quiz(&self, quiz_id: QuizId, language: Option<String>) -> Option<QuizOutput> {
         if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            let questions = self.get_questions_by_quiz(quiz_id, language.clone());
+            let is_finished = QuizChain::is_quiz_finished(&quiz);
+            let entry_fee = QuizChain::get_entry_fee_output(&quiz);
+            let (title, description, language) = if let Some(translation) = self.get_translation(quiz_id, &language) {
+                (Some(translation.title), translation.description, language)
+            } else {
+                (quiz.title, quiz.description, quiz.language)
+            };
             Some(QuizOutput {
                 id: quiz_id,
-                title: quiz.title,
-                description: quiz.description,
-                language: quiz.language,
+                title,
+                description,
+                language,
+                languages: quiz.languages,
+                tags: quiz.tags,
                 finality_type: quiz.finality_type,
                 owner_id: quiz.owner_id,
                 status: quiz.status,
                 total_questions: quiz.total_questions,
+                questions_per_game: quiz.questions_per_game,
+                shuffle_options: quiz.shuffle_options,
                 unclaimed_rewards_ids: quiz.available_rewards_ids,
-                secret: quiz.secret,
+                secret: if is_finished { quiz.secret } else { None },
                 success_hash: quiz.success_hash,
-                questions: self.get_questions_by_quiz(quiz_id),
+                success_hash_commitment: quiz.success_hash_commitment,
+                questions,
                 available_rewards: self.get_unclaimed_rewards_by_quiz(quiz_id),
                 distributed_rewards: self.get_distributed_rewards_by_quiz(quiz_id),
                 revealed_answers: quiz.revealed_answers,
+                reveal_started_at: quiz.reveal_started_at,
                 timestamp: quiz.timestamp,
                 restart_allowed: quiz.restart_allowed,
+                starts_at: quiz.starts_at,
+                ends_at: quiz.ends_at,
                 token_account_id: quiz.token_account_id,
-                funded_amount: quiz.funded_amount
+                funded_amount: quiz.funded_amount,
+                clone_policy: quiz.clone_policy,
+                activated_at: quiz.activated_at,
+                cancel_grace_period: quiz.cancel_grace_period,
+                scheduled_activation_at: quiz.scheduled_activation.map(|scheduled_activation| scheduled_activation.activation_at),
+                revisable_answers: quiz.revisable_answers,
+                entry_fee,
+                eligibility_gates: quiz.eligibility_gates,
+                invitation_key: quiz.invitation_key.map(Base58PublicKey),
+                allowlist_only: quiz.allowlist_only,
+                max_players: quiz.max_players,
+                waitlist_enabled: quiz.waitlist_enabled,
+                player_timeout: quiz.player_timeout
             })
         }
         else {
@@ -746,26 +1170,41 @@ impl QuizChain {
         }
     }
 
-    pub fn get_active_quizzes (&self) -> Vec<QuizId> {
-        self.active_quizzes.to_vec()
-    }
-
-    pub fn get_questions_by_quiz(&self, quiz_id: QuizId) -> Vec<QuestionOutput> {
+    pub fn get_questions_by_quiz(&self, quiz_id: QuizId, language: Option<String>) -> Vec<QuestionOutput> {
         let mut questions: Vec<QuestionOutput> = Vec::new();
         if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            let translation = self.get_translation(quiz_id, &language);
             for question_id in 0u16..quiz.total_questions {
-                if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)) {
-                    questions.push(QuestionOutput {
-                        id: question_id,
-                        question: question.clone(),
-                        question_options: self.get_question_options_by_question_id(quiz_id, question_id, question.options_quantity),
-                    });
+                if let Some(mut question) = self.get_question_output(quiz_id, &quiz, question_id) {
+                    QuizChain::translate_question(&mut question, &translation);
+                    questions.push(question);
                 }
             }
         }
         questions
     }
 
+    // Answer hashes and accepted answers are shown only after the finish, the secret is hidden until then as well
+    pub(crate) fn get_question_output(&self, quiz_id: QuizId, quiz: &Quiz, question_id: QuestionId) -> Option<QuestionOutput> {
+        if let Some(mut question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id)) {
+            if !QuizChain::is_quiz_finished(quiz) {
+                question.answer_hash = None;
+                question.accepted_answer_hashes = None;
+            }
+            Some(QuestionOutput {
+                id: question_id,
+                question: question.clone(),
+                question_options: self.get_question_options_by_question_id(quiz_id, question_id, question.options_quantity),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn is_quiz_finished(quiz: &Quiz) -> bool {
+        [QuizStatus::Finished, QuizStatus::Expired, QuizStatus::Cancelled].contains(&quiz.status)
+    }
+
     pub fn get_question_options_by_question_id(&self, quiz_id: QuizId, question_id: QuestionId, options_quantity: u16) -> Vec<QuestionOptionOutput> {
         let mut question_options: Vec<QuestionOptionOutput> = Vec::new();
 
@@ -775,6 +1214,7 @@ impl QuizChain {
                     id: question_option_id,
                     content: question_option.content,
                     kind: question_option.kind,
+                    column: question_option.column,
                 });
             }
         }
@@ -791,7 +1231,7 @@ impl QuizChain {
         if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
             assert_eq!(quiz.finality_type, QuizFinalityType::DelayedReveal, "Hash reveal is not supported");
             assert!(quiz.revealed_answers.is_none(), "Quiz has answers");
-            assert_eq!(quiz.status, QuizStatus::Finished, "Quiz is not Finished");
+            assert!([QuizStatus::Finished, QuizStatus::Expired].contains(&quiz.status), "Quiz is not Finished");
 
             quiz.success_hash = Some(hash);
             self.quizzes.insert(&quiz_id, &quiz);
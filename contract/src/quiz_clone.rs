@@ -0,0 +1,202 @@
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    #[payable]
+    pub fn clone_quiz(&mut self,
+                      source_quiz_id: QuizId,
+                      title: Option<String>,
+                      description: Option<String>,
+                      language: Option<String>,
+                      finality_type: QuizFinalityType,
+                      rewards: Vec<RewardInput>,
+                      secret: Option<String>,
+                      success_hash: Option<String>,
+                      success_hash_commitment: Option<Hash>,
+                      restart_allowed: bool,
+                      starts_at: Option<Timestamp>,
+                      ends_at: Option<Timestamp>,
+                      token_account_id: Option<TokenAccountId>) -> QuizId {
+        self.clone_quiz_internal(env::predecessor_account_id(),
+                                 env::predecessor_account_id(),
+                                 source_quiz_id,
+                                 title,
+                                 description,
+                                 language,
+                                 finality_type,
+                                 rewards,
+                                 secret,
+                                 success_hash,
+                                 success_hash_commitment,
+                                 restart_allowed,
+                                 starts_at,
+                                 ends_at,
+                                 env::attached_deposit(),
+                                 token_account_id)
+    }
+
+    // Copies questions and options of the source quiz into a new quiz with its own rewards, secret and success hash.
+    // Answer hashes are salted with the secret or the salt of the source quiz, so they are copied only for
+    // its owner while the salt is secret, and the clone has to be activated with the same secret or salt
+    pub(crate) fn clone_quiz_internal(&mut self,
+                                      owner_id: AccountId,
+                                      cloner_id: AccountId,
+                                      source_quiz_id: QuizId,
+                                      title: Option<String>,
+                                      description: Option<String>,
+                                      language: Option<String>,
+                                      finality_type: QuizFinalityType,
+                                      rewards: Vec<RewardInput>,
+                                      secret: Option<String>,
+                                      success_hash: Option<String>,
+                                      success_hash_commitment: Option<Hash>,
+                                      restart_allowed: bool,
+                                      starts_at: Option<Timestamp>,
+                                      ends_at: Option<Timestamp>,
+                                      deposit: Balance,
+                                      token_account_id: Option<TokenAccountId>) -> QuizId {
+        if let Some(source_quiz) = self.quizzes.get(&source_quiz_id) {
+            assert_ne!(source_quiz.status, QuizStatus::Funded, "Quiz has no questions");
+            assert!(self.is_clone_allowed(source_quiz_id, &source_quiz, &cloner_id), "No access to clone the quiz");
+            if source_quiz.questions_per_game.is_some() {
+                assert_eq!(finality_type, QuizFinalityType::Direct, "Question pools are supported for Direct quizzes only");
+            }
+
+            let mut questions: Vec<QuestionInput> = Vec::new();
+            let mut all_question_options: Vec<Vec<QuestionOption>> = Vec::new();
+            for question_id in 0..source_quiz.total_questions {
+                if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(source_quiz_id, question_id)) {
+                    if question.answer_hash.is_some() || question.accepted_answer_hashes.is_some() {
+                        assert!(source_quiz.owner_id == cloner_id && source_quiz.salt.is_none(),
+                                "Answer hashes of the quiz may be cloned only by its owner before the reveal");
+                    }
+                    all_question_options.push((0..question.options_quantity)
+                        .filter_map(|question_option_id|
+                            self.question_options.get(&QuizChain::get_question_option_by_quiz(source_quiz_id, question_id, question_option_id)))
+                        .collect());
+                    questions.push(QuestionInput {
+                        kind: question.kind,
+                        content: question.content,
+                        hint: question.hint,
+                        time_limit: question.time_limit,
+                        answer_hash: question.answer_hash,
+                        text_normalization: question.text_normalization,
+                        accepted_answer_hashes: question.accepted_answer_hashes,
+                        numeric_settings: question.numeric_settings,
+                    });
+                }
+            }
+
+            let quiz_id = self.create_quiz_internal(owner_id.clone(),
+                                                    title.or(source_quiz.title).unwrap_or_default(),
+                                                    description.or(source_quiz.description),
+                                                    language.or(source_quiz.language),
+                                                    finality_type,
+                                                    questions,
+                                                    all_question_options,
+                                                    rewards,
+                                                    secret.clone(),
+                                                    success_hash.clone(),
+                                                    success_hash_commitment.clone(),
+                                                    restart_allowed,
+                                                    starts_at,
+                                                    ends_at,
+                                                    deposit,
+                                                    token_account_id);
+
+            if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+                for translation_language in source_quiz.languages {
+                    if let Some(translation) = self.quiz_translations.get(&QuizChain::get_translation_by_quiz(source_quiz_id, translation_language.clone())) {
+                        if quiz.language.as_ref() != Some(&translation_language) {
+                            self.quiz_translations.insert(&QuizChain::get_translation_by_quiz(quiz_id, translation_language.clone()), &translation);
+                            quiz.languages.push(translation_language);
+                        }
+                    }
+                }
+                self.internal_index_quiz_languages(quiz_id, &quiz);
+                quiz.questions_per_game = source_quiz.questions_per_game;
+                quiz.shuffle_options = source_quiz.shuffle_options;
+                self.quizzes.insert(&quiz_id, &quiz);
+            }
+
+            if let Some(secret_unwrapped) = secret {
+                self.activate_quiz_internal(owner_id, quiz_id, secret_unwrapped, success_hash, success_hash_commitment);
+            }
+
+            log!("Quiz {} cloned from quiz {}", quiz_id, source_quiz_id);
+            quiz_id
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    pub fn set_clone_policy(&mut self, quiz_id: QuizId, clone_policy: ClonePolicy) {
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+            QuizChain::assert_current_user(&quiz.owner_id);
+
+            quiz.clone_policy = clone_policy;
+            self.quizzes.insert(&quiz_id, &quiz);
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    pub fn add_clone_license(&mut self, quiz_id: QuizId, account_id: ValidAccountId) {
+        self.assert_quiz_owner(quiz_id);
+
+        let mut licenses = self.clone_licenses.get(&quiz_id).unwrap_or_else(|| UnorderedSet::new(StorageKey::CloneLicensesByQuiz {
+            quiz_id,
+        }));
+        licenses.insert(&account_id.into());
+        self.clone_licenses.insert(&quiz_id, &licenses);
+    }
+
+    pub fn remove_clone_license(&mut self, quiz_id: QuizId, account_id: ValidAccountId) {
+        self.assert_quiz_owner(quiz_id);
+
+        if let Some(mut licenses) = self.clone_licenses.get(&quiz_id) {
+            licenses.remove(&account_id.into());
+            self.clone_licenses.insert(&quiz_id, &licenses);
+        }
+    }
+
+    pub fn get_clone_licenses(&self, quiz_id: QuizId, from_index: u64, limit: u64) -> Vec<AccountId> {
+        if let Some(licenses) = self.clone_licenses.get(&quiz_id) {
+            let accounts = licenses.as_vector();
+            (from_index..std::cmp::min(from_index + limit, accounts.len()))
+                .filter_map(|index| accounts.get(index))
+                .collect()
+        } else {
+            [].to_vec()
+        }
+    }
+
+    pub fn can_clone_quiz(&self, quiz_id: QuizId, account_id: ValidAccountId) -> bool {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            self.is_clone_allowed(quiz_id, &quiz, &account_id.into())
+        } else {
+            false
+        }
+    }
+
+    fn is_clone_allowed(&self, quiz_id: QuizId, quiz: &Quiz, account_id: &AccountId) -> bool {
+        if quiz.owner_id == *account_id {
+            return true;
+        }
+        match quiz.clone_policy {
+            ClonePolicy::OwnerOnly => false,
+            ClonePolicy::Public => true,
+            ClonePolicy::Licensed => self.clone_licenses.get(&quiz_id)
+                .map(|licenses| licenses.contains(account_id))
+                .unwrap_or(false),
+        }
+    }
+
+    fn assert_quiz_owner(&self, quiz_id: QuizId) {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            QuizChain::assert_current_user(&quiz.owner_id);
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+}
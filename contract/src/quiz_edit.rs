@@ -0,0 +1,210 @@
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    pub fn update_quiz_details(&mut self, quiz_id: QuizId, title: String, description: Option<String>, language: Option<String>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+
+        self.internal_unindex_quiz_languages(quiz_id, &quiz);
+        quiz.title = Some(title);
+        quiz.description = description;
+        quiz.language = language;
+        self.internal_index_quiz_languages(quiz_id, &quiz);
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn replace_question(&mut self, quiz_id: QuizId, question_id: QuestionId, question: QuestionInput, question_options: Vec<QuestionOption>) {
+        let quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(question_id < quiz.total_questions, "Question not found");
+
+        let question = QuizChain::get_question_from_input(&question);
+        let question_translation = QuizChain::get_base_question_translation(&question, &question_options);
+        self.internal_update_translations(quiz_id, &quiz, |questions|
+            if let Some(translated_question) = questions.get_mut(question_id as usize) {
+                *translated_question = question_translation.clone();
+            });
+        self.internal_store_question(quiz_id, question_id, question, question_options);
+    }
+
+    // Questions starting from question_id are moved one position further
+    pub fn insert_question(&mut self, quiz_id: QuizId, question_id: QuestionId, question: QuestionInput, question_options: Vec<QuestionOption>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(question_id <= quiz.total_questions, "Illegal question position");
+
+        for moved_question_id in (question_id..quiz.total_questions).rev() {
+            self.internal_move_question(quiz_id, moved_question_id, moved_question_id + 1);
+        }
+        let question = QuizChain::get_question_from_input(&question);
+        let question_translation = QuizChain::get_base_question_translation(&question, &question_options);
+        self.internal_update_translations(quiz_id, &quiz, |questions|
+            if question_id as usize <= questions.len() {
+                questions.insert(question_id as usize, question_translation.clone());
+            });
+        self.internal_store_question(quiz_id, question_id, question, question_options);
+
+        quiz.total_questions += 1;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn remove_question(&mut self, quiz_id: QuizId, question_id: QuestionId) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(question_id < quiz.total_questions, "Question not found");
+        assert!(quiz.total_questions > 1, "Quiz should have at least one question");
+
+        for moved_question_id in question_id + 1..quiz.total_questions {
+            self.internal_move_question(quiz_id, moved_question_id, moved_question_id - 1);
+        }
+        self.internal_remove_question(quiz_id, quiz.total_questions - 1);
+        self.internal_update_translations(quiz_id, &quiz, |questions|
+            if (question_id as usize) < questions.len() {
+                questions.remove(question_id as usize);
+            });
+
+        quiz.total_questions -= 1;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Rewards may be split in a different way, but their total stays the same as it was funded
+    pub fn update_rewards(&mut self, quiz_id: QuizId, rewards: Vec<RewardInput>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(!rewards.is_empty(), "Data not found");
+
+        let mut current_rewards_total: Balance = 0;
+        for reward_id in &quiz.available_rewards_ids {
+            let reward_index = QuizChain::get_reward_by_quiz(quiz_id, *reward_id);
+            if let Some(reward) = self.rewards.get(&reward_index) {
+                current_rewards_total += reward.amount;
+            }
+            self.rewards.remove(&reward_index);
+        }
+
+        let mut rewards_total: Balance = 0;
+        let mut unclaimed_rewards_ids = Vec::new();
+        for (index, reward) in rewards.iter().enumerate() {
+            rewards_total += reward.amount.0;
+            let reward_id: RewardId = index as RewardId;
+            self.rewards.insert(
+                &QuizChain::get_reward_by_quiz(quiz_id, reward_id),
+                &Reward {
+                    amount: reward.amount.0,
+                    winner_account_id: None,
+                    claimed: false,
+                });
+            unclaimed_rewards_ids.push(reward_id);
+        }
+
+        assert_eq!(current_rewards_total, rewards_total,
+                   "Illegal rewards. Total available rewards: {}", current_rewards_total);
+
+        quiz.available_rewards_ids = unclaimed_rewards_ids;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Every player gets questions_per_game random questions of the quiz. Answers of such quizzes
+    // are verified question by question when they are sent, so every question needs an answer_hash
+    // built with the quiz secret and the quiz is activated without a success hash commitment.
+    // Answer hashes are stored in the contract state, so unlike the committed success hash they
+    // may be checked offline by anyone reading it
+    pub fn set_question_pool(&mut self, quiz_id: QuizId, questions_per_game: Option<u16>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+
+        if let Some(questions_per_game_value) = questions_per_game {
+            assert_eq!(quiz.finality_type, QuizFinalityType::Direct, "Question pools are supported for Direct quizzes only");
+            assert!(questions_per_game_value > 0 && questions_per_game_value <= quiz.total_questions, "Illegal questions_per_game");
+        }
+
+        quiz.questions_per_game = questions_per_game;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Every player sees options of the questions in a different order
+    pub fn set_shuffle_options(&mut self, quiz_id: QuizId, shuffle_options: bool) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert_ne!(quiz.finality_type, QuizFinalityType::CommitReveal, "Answers of this quiz are sent with commit_answers");
+
+        quiz.shuffle_options = shuffle_options;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Time after the activation when the owner is allowed to cancel the quiz in progress
+    pub fn set_cancel_grace_period(&mut self, quiz_id: QuizId, cancel_grace_period: Option<Timestamp>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+
+        quiz.cancel_grace_period = cancel_grace_period;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Players may change sent answers until they call submit_game
+    pub fn set_revisable_answers(&mut self, quiz_id: QuizId, revisable_answers: bool) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert_ne!(quiz.finality_type, QuizFinalityType::CommitReveal, "Answers of this quiz are sent with commit_answers");
+
+        quiz.revisable_answers = revisable_answers;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub(crate) fn get_locked_quiz_for_owner(&self, quiz_id: QuizId) -> Quiz {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            QuizChain::assert_current_user(&quiz.owner_id);
+            assert_eq!(quiz.status, QuizStatus::Locked, "Quiz is not Locked");
+            quiz
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    pub(crate) fn get_question_from_input(question: &QuestionInput) -> Question {
+        QuizChain::assert_valid_text_answer_settings(question);
+        QuizChain::assert_valid_numeric_settings(question);
+        Question {
+            kind: question.kind,
+            content: question.content.clone(),
+            hint: question.hint.clone(),
+            options_quantity: 0,
+            time_limit: question.time_limit,
+            answer_hash: question.answer_hash.clone(),
+            text_normalization: question.text_normalization.clone(),
+            accepted_answer_hashes: question.accepted_answer_hashes.clone(),
+            numeric_settings: question.numeric_settings.clone(),
+        }
+    }
+
+    // Stores the question with its options and removes options left from the previous version of the question
+    pub(crate) fn internal_store_question(&mut self, quiz_id: QuizId, question_id: QuestionId, mut question: Question, question_options: Vec<QuestionOption>) {
+        let question_index = QuizChain::get_question_by_quiz(quiz_id, question_id);
+        let previous_options_quantity = self.questions.get(&question_index).map(|previous_question| previous_question.options_quantity).unwrap_or(0);
+
+        QuizChain::assert_valid_question_options(question.kind, &question_options);
+
+        let options_quantity = question_options.len() as u16;
+        for (question_option_id, question_option) in question_options.iter().enumerate() {
+            self.question_options.insert(
+                &QuizChain::get_question_option_by_quiz(quiz_id, question_id, question_option_id as QuestionOptionId),
+                question_option);
+        }
+        for question_option_id in options_quantity..previous_options_quantity {
+            self.question_options.remove(&QuizChain::get_question_option_by_quiz(quiz_id, question_id, question_option_id));
+        }
+
+        question.options_quantity = options_quantity;
+        self.questions.insert(&question_index, &question);
+    }
+
+    fn internal_move_question(&mut self, quiz_id: QuizId, from_question_id: QuestionId, to_question_id: QuestionId) {
+        if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, from_question_id)) {
+            let question_options: Vec<QuestionOption> = (0..question.options_quantity)
+                .filter_map(|question_option_id|
+                    self.question_options.get(&QuizChain::get_question_option_by_quiz(quiz_id, from_question_id, question_option_id)))
+                .collect();
+            self.internal_store_question(quiz_id, to_question_id, question, question_options);
+        }
+    }
+
+    fn internal_remove_question(&mut self, quiz_id: QuizId, question_id: QuestionId) {
+        if let Some(question) = self.questions.remove(&QuizChain::get_question_by_quiz(quiz_id, question_id)) {
+            for question_option_id in 0..question.options_quantity {
+                self.question_options.remove(&QuizChain::get_question_option_by_quiz(quiz_id, question_id, question_option_id));
+            }
+        }
+    }
+}
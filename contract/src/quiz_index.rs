@@ -0,0 +1,149 @@
+use std::cmp::min;
+
+use crate::*;
+
+const MAX_TAGS: usize = 10;
+const MAX_TAG_LENGTH: usize = 32;
+
+// Finished, Cancelled and Expired are final statuses
+const QUIZ_STATUS_TRANSITIONS: [(QuizStatus, QuizStatus); 7] = [
+    (QuizStatus::Funded, QuizStatus::Locked),
+    (QuizStatus::Funded, QuizStatus::Cancelled),
+    (QuizStatus::Locked, QuizStatus::InProgress),
+    (QuizStatus::Locked, QuizStatus::Cancelled),
+    (QuizStatus::InProgress, QuizStatus::Finished),
+    (QuizStatus::InProgress, QuizStatus::Cancelled),
+    (QuizStatus::InProgress, QuizStatus::Expired),
+];
+
+fn add_to_index<K: BorshSerialize + BorshDeserialize>(index: &mut LookupMap<K, UnorderedSet<QuizId>>, key: &K, quiz_id: QuizId,
+                                                      storage_key: StorageKey) {
+    let mut quiz_ids = index.get(key).unwrap_or_else(|| UnorderedSet::new(storage_key));
+    quiz_ids.insert(&quiz_id);
+    index.insert(key, &quiz_ids);
+}
+
+fn remove_from_index<K: BorshSerialize + BorshDeserialize>(index: &mut LookupMap<K, UnorderedSet<QuizId>>, key: &K, quiz_id: QuizId) {
+    if let Some(mut quiz_ids) = index.get(key) {
+        quiz_ids.remove(&quiz_id);
+        index.insert(key, &quiz_ids);
+    }
+}
+
+#[near_bindgen]
+impl QuizChain {
+    pub fn set_quiz_tags(&mut self, quiz_id: QuizId, tags: Vec<String>) {
+        if let Some(mut quiz) = self.quizzes.get(&quiz_id) {
+            QuizChain::assert_current_user(&quiz.owner_id);
+            assert!(tags.len() <= MAX_TAGS, "Too many tags");
+
+            let mut new_tags: Vec<String> = Vec::new();
+            for tag in tags {
+                let tag = tag.trim().to_lowercase();
+                assert!(!tag.is_empty() && tag.len() <= MAX_TAG_LENGTH, "Illegal tag");
+                if !new_tags.contains(&tag) {
+                    new_tags.push(tag);
+                }
+            }
+
+            for tag in &quiz.tags {
+                remove_from_index(&mut self.quizzes_by_tag, tag, quiz_id);
+            }
+            for tag in &new_tags {
+                add_to_index(&mut self.quizzes_by_tag, tag, quiz_id, StorageKey::QuizzesByTagSet { tag: tag.clone() });
+            }
+
+            quiz.tags = new_tags;
+            self.quizzes.insert(&quiz_id, &quiz);
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    pub fn get_quizzes_by_tag(&self, tag: String, from_index: usize, limit: usize) -> Vec<QuizOutput> {
+        self.get_quizzes_by_index(self.quizzes_by_tag.get(&tag.trim().to_lowercase()), from_index, limit)
+    }
+
+    pub fn get_quizzes_by_language(&self, language: String, from_index: usize, limit: usize) -> Vec<QuizOutput> {
+        self.get_quizzes_by_index(self.quizzes_by_language.get(&language), from_index, limit)
+    }
+
+    pub fn get_quizzes_by_token(&self, token_account_id: Option<TokenAccountId>, from_index: usize, limit: usize) -> Vec<QuizOutput> {
+        self.get_quizzes_by_index(self.quizzes_by_token.get(&QuizChain::unwrap_token_id(&token_account_id)), from_index, limit)
+    }
+
+    pub fn get_quizzes_by_status(&self, status: QuizStatus, from_index: usize, limit: usize) -> Vec<QuizOutput> {
+        self.get_quizzes_by_index(self.quizzes_by_status.get(&status), from_index, limit)
+    }
+
+    fn get_quizzes_by_index(&self, quiz_ids: Option<UnorderedSet<QuizId>>, from_index: usize, limit: usize) -> Vec<QuizOutput> {
+        let mut quizzes: Vec<QuizOutput> = Vec::new();
+        if let Some(quiz_ids) = quiz_ids {
+            let quiz_ids_qty = quiz_ids.len() as usize;
+            assert!(from_index <= quiz_ids_qty, "Illegal from_index");
+            let limit_id = min(from_index + limit, quiz_ids_qty);
+            let quiz_ids = quiz_ids.as_vector();
+            for quiz_index in from_index..limit_id {
+                if let Some(quiz) = quiz_ids.get(quiz_index as u64).and_then(|quiz_id| self.get_quiz(quiz_id, None)) {
+                    quizzes.push(quiz);
+                }
+            }
+        }
+        quizzes
+    }
+
+    // Every status change goes through this method to keep indexes up to date
+    pub(crate) fn internal_set_quiz_status(&mut self, quiz_id: QuizId, quiz: &mut Quiz, status: QuizStatus) {
+        QuizChain::assert_quiz_status_transition(quiz.status, status);
+
+        remove_from_index(&mut self.quizzes_by_status, &quiz.status, quiz_id);
+        add_to_index(&mut self.quizzes_by_status, &status, quiz_id, StorageKey::QuizzesByStatusSet { status });
+
+        self.internal_remove_active_quiz(quiz_id, quiz);
+        if status == QuizStatus::InProgress {
+            self.internal_add_active_quiz(quiz_id, quiz);
+        }
+
+        quiz.status = status;
+    }
+
+    pub(crate) fn assert_quiz_status_transition(from: QuizStatus, to: QuizStatus) {
+        assert!(QUIZ_STATUS_TRANSITIONS.contains(&(from, to)), "Quiz status can't be changed from {:?} to {:?}", from, to);
+    }
+
+    pub(crate) fn internal_index_quiz(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        add_to_index(&mut self.quizzes_by_status, &quiz.status, quiz_id, StorageKey::QuizzesByStatusSet { status: quiz.status });
+
+        let token_account_id = QuizChain::unwrap_token_id(&quiz.token_account_id);
+        add_to_index(&mut self.quizzes_by_token, &token_account_id, quiz_id,
+                     StorageKey::QuizzesByTokenSet { token_account_id: token_account_id.clone() });
+
+        for tag in &quiz.tags {
+            add_to_index(&mut self.quizzes_by_tag, tag, quiz_id, StorageKey::QuizzesByTagSet { tag: tag.clone() });
+        }
+
+        self.internal_index_quiz_languages(quiz_id, quiz);
+    }
+
+    pub(crate) fn internal_unindex_quiz(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        remove_from_index(&mut self.quizzes_by_status, &quiz.status, quiz_id);
+        remove_from_index(&mut self.quizzes_by_token, &QuizChain::unwrap_token_id(&quiz.token_account_id), quiz_id);
+        for tag in &quiz.tags {
+            remove_from_index(&mut self.quizzes_by_tag, tag, quiz_id);
+        }
+        self.internal_unindex_quiz_languages(quiz_id, quiz);
+    }
+
+    // Quizzes are indexed by the base language and by languages of all translations
+    pub(crate) fn internal_index_quiz_languages(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        for language in quiz.language.iter().chain(quiz.languages.iter()) {
+            add_to_index(&mut self.quizzes_by_language, language, quiz_id, StorageKey::QuizzesByLanguageSet { language: language.clone() });
+        }
+    }
+
+    pub(crate) fn internal_unindex_quiz_languages(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        for language in quiz.language.iter().chain(quiz.languages.iter()) {
+            remove_from_index(&mut self.quizzes_by_language, language, quiz_id);
+        }
+    }
+}
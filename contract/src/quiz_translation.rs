@@ -0,0 +1,97 @@
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    // Translations change only texts shown to players. Answers are hashed with option ids and texts
+    // of the base language, so players of all languages compete for the same rewards.
+    // Text answers in other languages should be listed in accepted_answer_hashes
+    pub fn set_quiz_translation(&mut self, quiz_id: QuizId, language: String, translation: QuizTranslation) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert_ne!(Some(language.clone()), quiz.language, "Base language of the quiz");
+
+        self.assert_valid_translation(quiz_id, &quiz, &translation);
+
+        self.quiz_translations.insert(&QuizChain::get_translation_by_quiz(quiz_id, language.clone()), &translation);
+        if !quiz.languages.contains(&language) {
+            quiz.languages.push(language);
+            self.internal_index_quiz_languages(quiz_id, &quiz);
+            self.quizzes.insert(&quiz_id, &quiz);
+        }
+    }
+
+    pub fn remove_quiz_translation(&mut self, quiz_id: QuizId, language: String) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(quiz.languages.contains(&language), "Translation not found");
+
+        self.quiz_translations.remove(&QuizChain::get_translation_by_quiz(quiz_id, language.clone()));
+        self.internal_unindex_quiz_languages(quiz_id, &quiz);
+        quiz.languages.retain(|quiz_language| quiz_language != &language);
+        self.internal_index_quiz_languages(quiz_id, &quiz);
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn get_quiz_translation(&self, quiz_id: QuizId, language: String) -> Option<QuizTranslation> {
+        self.quiz_translations.get(&QuizChain::get_translation_by_quiz(quiz_id, language))
+    }
+
+    pub(crate) fn get_translation(&self, quiz_id: QuizId, language: &Option<String>) -> Option<QuizTranslation> {
+        if let Some(language) = language {
+            self.quiz_translations.get(&QuizChain::get_translation_by_quiz(quiz_id, language.clone()))
+        } else {
+            None
+        }
+    }
+
+    // Translations follow edited questions, inserted and replaced questions are shown
+    // in the base language until their translation is set again
+    pub(crate) fn internal_update_translations<F>(&mut self, quiz_id: QuizId, quiz: &Quiz, update: F)
+        where F: Fn(&mut Vec<QuestionTranslation>) {
+        for language in &quiz.languages {
+            let translation_index = QuizChain::get_translation_by_quiz(quiz_id, language.clone());
+            if let Some(mut translation) = self.quiz_translations.get(&translation_index) {
+                update(&mut translation.questions);
+                self.quiz_translations.insert(&translation_index, &translation);
+            }
+        }
+    }
+
+    pub(crate) fn get_base_question_translation(question: &Question, question_options: &[QuestionOption]) -> QuestionTranslation {
+        QuestionTranslation {
+            content: question.content.clone(),
+            hint: question.hint.clone(),
+            question_options: question_options.iter().map(|question_option| question_option.content.clone()).collect(),
+        }
+    }
+
+    // Should be applied before options are shuffled, since option ids are used to find translations
+    pub(crate) fn translate_question(question: &mut QuestionOutput, translation: &Option<QuizTranslation>) {
+        if let Some(question_translation) = translation.as_ref().and_then(|translation| translation.questions.get(question.id as usize)) {
+            question.question.content = question_translation.content.clone();
+            question.question.hint = question_translation.hint.clone();
+            for question_option in question.question_options.iter_mut() {
+                if let Some(content) = question_translation.question_options.get(question_option.id as usize) {
+                    question_option.content = content.clone();
+                }
+            }
+        }
+    }
+
+    // Questions may be edited after the translation was added, so translations are checked again on activation
+    pub(crate) fn assert_valid_translations(&self, quiz_id: QuizId, quiz: &Quiz) {
+        for language in &quiz.languages {
+            if let Some(translation) = self.quiz_translations.get(&QuizChain::get_translation_by_quiz(quiz_id, language.clone())) {
+                self.assert_valid_translation(quiz_id, quiz, &translation);
+            }
+        }
+    }
+
+    fn assert_valid_translation(&self, quiz_id: QuizId, quiz: &Quiz, translation: &QuizTranslation) {
+        assert_eq!(translation.questions.len(), quiz.total_questions as usize, "Translation doesn't match questions of the quiz");
+        for (question_id, question_translation) in translation.questions.iter().enumerate() {
+            if let Some(question) = self.questions.get(&QuizChain::get_question_by_quiz(quiz_id, question_id as QuestionId)) {
+                assert_eq!(question_translation.question_options.len(), question.options_quantity as usize,
+                           "Translation doesn't match options of question {}", question_id);
+            }
+        }
+    }
+}
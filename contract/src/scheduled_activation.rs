@@ -0,0 +1,49 @@
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    // The owner commits to the secret of the quiz with secret_commitment = sha256(secret), so the quiz
+    // may be activated at activation_at by anyone who knows the secret, without the owner being online
+    pub fn schedule_quiz_activation(&mut self, quiz_id: QuizId, activation_at: Timestamp, secret_commitment: Hash,
+                                    success_hash: Option<Hash>, success_hash_commitment: Option<Hash>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(activation_at > env::block_timestamp(), "Illegal activation time");
+        if let Some(ends_at) = quiz.ends_at {
+            assert!(activation_at < ends_at, "Quiz should be activated before the end");
+        }
+        assert_eq!(secret_commitment.chars().count(), 64, "Illegal hash length");
+        self.assert_quiz_ready_for_activation(quiz_id, &quiz, &success_hash, &success_hash_commitment);
+
+        quiz.scheduled_activation = Some(ScheduledActivation {
+            activation_at,
+            secret_commitment,
+            success_hash,
+            success_hash_commitment,
+        });
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn cancel_quiz_activation(&mut self, quiz_id: QuizId) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        assert!(quiz.scheduled_activation.is_some(), "Activation is not scheduled");
+
+        quiz.scheduled_activation = None;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn activate_scheduled_quiz(&mut self, quiz_id: QuizId, secret: Secret) {
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            if let Some(scheduled_activation) = quiz.scheduled_activation {
+                assert!(env::block_timestamp() >= scheduled_activation.activation_at, "Too early to activate");
+                assert_eq!(QuizChain::get_hash(secret.clone()), scheduled_activation.secret_commitment, "Secret doesn't match the commitment");
+
+                self.activate_quiz_internal(quiz.owner_id, quiz_id, secret,
+                                            scheduled_activation.success_hash, scheduled_activation.success_hash_commitment);
+            } else {
+                panic!("Activation is not scheduled");
+            }
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use near_sdk::test_utils::{accounts, testing_env_with_promise_results, VMContextBuilder};
+use near_sdk::{testing_env, MockedBlockchain, PromiseResult};
+
+use crate::*;
+
+const REWARD: Balance = 1_000_000_000_000_000_000_000_000;
+const SECRET: &str = "77777";
+const CREATED_AT: Timestamp = DAY_IN_NANOSECONDS;
+
+fn get_context(predecessor_account_id: ValidAccountId, block_timestamp: Timestamp, attached_deposit: Balance) -> VMContextBuilder {
+    let mut context = VMContextBuilder::new();
+    context
+        .current_account_id(accounts(5))
+        .predecessor_account_id(predecessor_account_id)
+        .block_timestamp(block_timestamp)
+        .attached_deposit(attached_deposit);
+    context
+}
+
+fn set_context(predecessor_account_id: ValidAccountId, block_timestamp: Timestamp, attached_deposit: Balance) {
+    testing_env!(get_context(predecessor_account_id, block_timestamp, attached_deposit).build());
+}
+
+fn text_option(content: &str) -> QuestionOption {
+    QuestionOption {
+        content: content.to_string(),
+        kind: QuestionOptionKind::Text,
+        column: None,
+    }
+}
+
+// Active quiz of alice with a single OneChoice question and a single reward
+fn create_quiz(contract: &mut QuizChain, finality_type: QuizFinalityType, ends_at: Option<Timestamp>,
+               success_hash_commitment: Option<Hash>) -> QuizId {
+    set_context(accounts(0), CREATED_AT, REWARD + REWARD / 100);
+    contract.create_quiz("Test QUIZ".to_string(), None, None, finality_type,
+                         vec![QuestionInput {
+                             kind: QuestionKind::OneChoice,
+                             content: "Какое сейчас время года?".to_string(),
+                             hint: None,
+                             time_limit: None,
+                             answer_hash: None,
+                             text_normalization: None,
+                             accepted_answer_hashes: None,
+                             numeric_settings: None,
+                         }],
+                         vec![vec![text_option("Зима"), text_option("Осень")]],
+                         vec![RewardInput { amount: REWARD.into() }],
+                         Some(SECRET.to_string()), None, success_hash_commitment, false, None, ends_at, None)
+}
+
+fn play_game(contract: &mut QuizChain, quiz_id: QuizId, account_id: ValidAccountId, question_option_id: QuestionOptionId) {
+    set_context(account_id.clone(), CREATED_AT + SECOND_IN_NANOSECONDS, 0);
+    contract.start_game(quiz_id, None, None);
+    contract.send_answer(quiz_id, 0, Some(vec![question_option_id]), None, None);
+}
+
+fn get_winner(contract: &QuizChain, quiz_id: QuizId) -> Option<AccountId> {
+    contract.rewards.get(&QuizChain::get_reward_by_quiz(quiz_id, 0)).unwrap().winner_account_id
+}
+
+#[test]
+fn delayed_reveal_quiz_without_final_hash_is_rewarded_to_finished_players() {
+    set_context(accounts(0), CREATED_AT, 0);
+    let mut contract = QuizChain::new();
+    let ends_at = CREATED_AT + DAY_IN_NANOSECONDS;
+    let quiz_id = create_quiz(&mut contract, QuizFinalityType::DelayedReveal, Some(ends_at), None);
+    play_game(&mut contract, quiz_id, accounts(1), 0);
+
+    set_context(accounts(2), ends_at + DAY_IN_NANOSECONDS + 1, 0);
+    contract.finalize_quiz(quiz_id);
+
+    assert_eq!(contract.quizzes.get(&quiz_id).unwrap().status, QuizStatus::Finished);
+    assert_eq!(get_winner(&contract, quiz_id), Some(accounts(1).into()));
+}
+
+#[test]
+#[should_panic(expected = "Too early to finalize")]
+fn delayed_reveal_quiz_is_not_finalized_during_the_reveal_day() {
+    set_context(accounts(0), CREATED_AT, 0);
+    let mut contract = QuizChain::new();
+    let ends_at = CREATED_AT + DAY_IN_NANOSECONDS;
+    let quiz_id = create_quiz(&mut contract, QuizFinalityType::DelayedReveal, Some(ends_at), None);
+    play_game(&mut contract, quiz_id, accounts(1), 0);
+
+    set_context(accounts(2), ends_at + 1, 0);
+    contract.finalize_quiz(quiz_id);
+}
+
+#[test]
+fn committed_quiz_without_end_is_rewarded_to_finished_players_after_the_default_end() {
+    set_context(accounts(0), CREATED_AT, 0);
+    let mut contract = QuizChain::new();
+    let success_hash = QuizChain::get_hash(format!("{}{}", QuizChain::get_hash(SECRET.to_string()), "осень"));
+    let success_hash_commitment = QuizChain::get_success_hash_commitment(&success_hash, "salt");
+    let quiz_id = create_quiz(&mut contract, QuizFinalityType::Direct, None, Some(success_hash_commitment));
+    play_game(&mut contract, quiz_id, accounts(1), 1);
+
+    set_context(accounts(2), CREATED_AT + DAY_IN_NANOSECONDS * 31 + 1, 0);
+    contract.finalize_quiz(quiz_id);
+
+    assert_eq!(contract.quizzes.get(&quiz_id).unwrap().status, QuizStatus::Finished);
+    assert_eq!(get_winner(&contract, quiz_id), Some(accounts(1).into()));
+}
+
+#[test]
+fn small_entry_fee_is_shared_by_rates() {
+    let entry_fee = EntryFee {
+        amount: 9999,
+        prize_pool_rate: 8000,
+        owner_rate: 1900,
+        service_rate: 100,
+    };
+    assert_eq!(QuizChain::get_entry_fee_shares(&entry_fee), (7999, 1899, 101));
+}
+
+#[test]
+fn entry_fee_is_refunded_if_the_quiz_is_finished_while_gates_are_checked() {
+    set_context(accounts(0), CREATED_AT, 0);
+    let mut contract = QuizChain::new();
+    let entry_fee: Balance = REWARD / 10;
+    let quiz_id = create_quiz(&mut contract, QuizFinalityType::DelayedReveal, None, None);
+    let mut quiz = contract.quizzes.get(&quiz_id).unwrap();
+    quiz.entry_fee = Some(EntryFee {
+        amount: entry_fee,
+        prize_pool_rate: 8000,
+        owner_rate: 1900,
+        service_rate: 100,
+    });
+    quiz.eligibility_gates = vec![EligibilityGate::Registry {
+        registry_account_id: accounts(4).into(),
+        method_name: "is_registered".to_string(),
+    }];
+    contract.quizzes.insert(&quiz_id, &quiz);
+
+    set_context(accounts(1), CREATED_AT + SECOND_IN_NANOSECONDS, entry_fee);
+    contract.start_game(quiz_id, None, None);
+
+    set_context(accounts(0), CREATED_AT + SECOND_IN_NANOSECONDS * 2, 1);
+    contract.reveal_final_hash(quiz_id, QuizChain::get_hash("final hash".to_string()));
+
+    testing_env_with_promise_results(get_context(accounts(5), CREATED_AT + SECOND_IN_NANOSECONDS * 3, 0).build(),
+                                     PromiseResult::Successful(b"true".to_vec()));
+    let unused_amount = contract.on_eligibility_checked(quiz_id, accounts(1).into(), None, entry_fee.into());
+
+    assert_eq!(unused_amount.0, 0);
+    assert!(contract.games.get(&QuizChain::get_quiz_by_user(quiz_id, accounts(1).into())).is_none());
+    assert!(contract.entry_fee_payments.get(&QuizChain::get_quiz_by_user(quiz_id, accounts(1).into())).is_none());
+}
@@ -0,0 +1,70 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    // Text answers are always compared in lowercase, other rules are enabled per question
+    pub(crate) fn normalize_text_answer(text: &str, text_normalization: &Option<TextNormalization>) -> String {
+        let mut text = text.to_lowercase();
+
+        if let Some(rules) = text_normalization {
+            if rules.unicode_normalization {
+                text = text.nfkc().collect();
+            }
+            if rules.fold_diacritics {
+                text = text.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect();
+            }
+            if rules.strip_punctuation {
+                text = text.chars().filter(|c| c.is_alphanumeric() || c.is_whitespace()).collect();
+            }
+            if rules.collapse_whitespace {
+                text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+            }
+            if rules.trim {
+                text = text.trim().to_string();
+            }
+        }
+
+        text
+    }
+
+    // Value of the text answer used in the answers hash. Accepted answers are salted with the salt of the
+    // success hash commitment, so once it is revealed any of them is replaced with the first accepted
+    // answer hash and the success hash is built with this hash for such questions
+    pub(crate) fn get_text_answer_value(salt: Option<&str>, question: &Question, normalized_text: &str) -> String {
+        if let (Some(accepted_answer_hashes), Some(salt)) = (question.accepted_answer_hashes.as_ref(), salt) {
+            if accepted_answer_hashes.contains(&QuizChain::get_answer_hash(salt, normalized_text)) {
+                return accepted_answer_hashes[0].clone();
+            }
+        }
+        normalized_text.to_string()
+    }
+
+    // Returns the answer stored for the player and the value used in the answers hash
+    pub(crate) fn get_text_answer(salt: Option<&str>, question: &Question, text: &str) -> (String, String) {
+        if question.kind == QuestionKind::Numeric {
+            let numeric_settings = question.numeric_settings.as_ref().expect("Numeric settings are missing");
+            let value = QuizChain::parse_numeric_answer(text, numeric_settings.decimals);
+            (QuizChain::format_numeric_answer(value, numeric_settings.decimals),
+             QuizChain::get_numeric_answer_value(numeric_settings, value))
+        } else {
+            let normalized_text = QuizChain::normalize_text_answer(text, &question.text_normalization);
+            let answer_value = QuizChain::get_text_answer_value(salt, question, &normalized_text);
+            (normalized_text, answer_value)
+        }
+    }
+
+    pub(crate) fn assert_valid_text_answer_settings(question: &QuestionInput) {
+        if question.text_normalization.is_some() || question.accepted_answer_hashes.is_some() {
+            assert_eq!(question.kind, QuestionKind::Text, "Only Text questions support answer normalization");
+        }
+        if let Some(accepted_answer_hashes) = question.accepted_answer_hashes.as_ref() {
+            assert!(!accepted_answer_hashes.is_empty(), "Accepted answers are missing");
+            for accepted_answer_hash in accepted_answer_hashes {
+                assert_eq!(accepted_answer_hash.chars().count(), 64, "Illegal hash length");
+            }
+        }
+    }
+}
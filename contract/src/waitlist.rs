@@ -0,0 +1,150 @@
+use std::cmp::min;
+
+use crate::*;
+
+#[near_bindgen]
+impl QuizChain {
+    // Accounts starting a game of a full quiz are added to the waitlist if it's enabled. Started players
+    // who abandon the game or don't answer for player_timeout seconds free their places for the waitlist
+    pub fn set_player_limits(&mut self, quiz_id: QuizId, max_players: Option<u64>, waitlist_enabled: bool, player_timeout: Option<u64>) {
+        let mut quiz = self.get_locked_quiz_for_owner(quiz_id);
+        if let Some(max_players) = max_players {
+            assert!(max_players > 0, "Illegal max_players");
+        }
+        if waitlist_enabled {
+            assert!(max_players.is_some(), "Waitlist requires max_players");
+            assert!(quiz.entry_fee.is_none(), "Waitlist is not available for quizzes with entry fee");
+        }
+        if let Some(player_timeout) = player_timeout {
+            assert!(player_timeout > 0, "Illegal player_timeout");
+        }
+
+        quiz.max_players = max_players;
+        quiz.waitlist_enabled = waitlist_enabled;
+        quiz.player_timeout = player_timeout;
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn abandon_game(&mut self, quiz_id: QuizId) {
+        let account_id = env::predecessor_account_id();
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+            if let Some(game) = self.games.get(&game_id) {
+                self.assert_game_can_be_released(&quiz, &game_id, &game);
+                self.internal_release_player(quiz_id, &quiz, account_id);
+            } else {
+                panic!("Game not found");
+            }
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    // Anyone may release a player who hasn't answered for player_timeout seconds
+    pub fn release_timed_out_player(&mut self, quiz_id: QuizId, account_id: ValidAccountId) {
+        let account_id: AccountId = account_id.into();
+        if let Some(quiz) = self.quizzes.get(&quiz_id) {
+            let player_timeout = quiz.player_timeout.expect("Quiz has no player timeout");
+            let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+            if let Some(game) = self.games.get(&game_id) {
+                self.assert_game_can_be_released(&quiz, &game_id, &game);
+                assert!(env::block_timestamp() > game.question_started_at + player_timeout * SECOND_IN_NANOSECONDS, "Player is not timed out");
+                self.internal_release_player(quiz_id, &quiz, account_id);
+            } else {
+                panic!("Game not found");
+            }
+        } else {
+            panic!("Quiz not found");
+        }
+    }
+
+    pub fn leave_waitlist(&mut self, quiz_id: QuizId) {
+        let waitlist_index = QuizChain::get_quiz_by_user(quiz_id, env::predecessor_account_id());
+        assert!(self.waitlisted_accounts.remove(&waitlist_index), "Account is not in the waitlist");
+    }
+
+    pub fn get_players(&self, quiz_id: QuizId, from_index: usize, limit: usize) -> Vec<AccountId> {
+        let mut account_ids: Vec<AccountId> = Vec::new();
+        if let Some(players) = self.players.get(&quiz_id) {
+            let players_qty = players.len() as usize;
+            assert!(from_index <= players_qty, "Illegal from_index");
+            let limit_id = min(from_index + limit, players_qty);
+            let players = players.as_vector();
+            for player_index in from_index..limit_id {
+                if let Some(account_id) = players.get(player_index as u64) {
+                    account_ids.push(account_id);
+                }
+            }
+        }
+        account_ids
+    }
+
+    // Accounts in the order of promotion
+    pub fn get_waitlist(&self, quiz_id: QuizId, from_index: usize, limit: usize) -> Vec<AccountId> {
+        if let Some(waitlist) = self.waitlists.get(&quiz_id) {
+            (waitlist.promoted_quantity..waitlist.account_ids.len())
+                .filter_map(|account_index| waitlist.account_ids.get(account_index))
+                .filter(|account_id| self.waitlisted_accounts.contains(&QuizChain::get_quiz_by_user(quiz_id, account_id.clone())))
+                .skip(from_index)
+                .take(limit)
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub(crate) fn assert_player_can_join(&self, quiz_id: QuizId, quiz: &Quiz, account_id: &AccountId) {
+        let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+        assert!(!self.released_players.contains(&game_id), "Player already left the quiz");
+        assert!(!self.waitlisted_accounts.contains(&game_id), "Account is already in the waitlist");
+        assert!(quiz.waitlist_enabled || self.has_free_player_place(quiz_id, quiz), "Quiz is full");
+    }
+
+    // Games of CommitReveal quizzes are counted as answered once the answers are committed
+    fn assert_game_can_be_released(&self, quiz: &Quiz, game_id: &QuizByUser, game: &Game) {
+        assert!(game.answers_quantity < QuizChain::get_questions_per_game(quiz), "Game is already finished");
+        assert!(self.answer_commitments.get(game_id).is_none(), "Answers are already committed");
+    }
+
+    pub(crate) fn has_free_player_place(&self, quiz_id: QuizId, quiz: &Quiz) -> bool {
+        quiz.max_players.map(|max_players| self.players.get(&quiz_id).map(|players| players.len()).unwrap_or(0) < max_players).unwrap_or(true)
+    }
+
+    pub(crate) fn internal_add_to_waitlist(&mut self, quiz_id: QuizId, account_id: AccountId) {
+        let mut waitlist = self.waitlists.get(&quiz_id).unwrap_or_else(|| Waitlist {
+            promoted_quantity: 0,
+            account_ids: Vector::new(StorageKey::WaitlistsByQuiz { quiz_id }),
+        });
+        waitlist.account_ids.push(&account_id);
+        self.waitlists.insert(&quiz_id, &waitlist);
+        self.waitlisted_accounts.insert(&QuizChain::get_quiz_by_user(quiz_id, account_id.clone()));
+
+        log!("Account {} added to the waitlist of quiz {}", account_id, quiz_id);
+    }
+
+    // Unfinished game is removed and the first account of the waitlist starts the game instead
+    fn internal_release_player(&mut self, quiz_id: QuizId, quiz: &Quiz, account_id: AccountId) {
+        let game_id = QuizChain::get_quiz_by_user(quiz_id, account_id.clone());
+        self.games.remove(&game_id);
+        self.released_players.insert(&game_id);
+        if let Some(mut players) = self.players.get(&quiz_id) {
+            players.remove(&account_id);
+            self.players.insert(&quiz_id, &players);
+        }
+
+        if [QuizStatus::InProgress, QuizStatus::Finished, QuizStatus::Expired].contains(&quiz.status) {
+            if let Some(mut waitlist) = self.waitlists.get(&quiz_id) {
+                while waitlist.promoted_quantity < waitlist.account_ids.len() {
+                    let promoted_account_id = waitlist.account_ids.get(waitlist.promoted_quantity).unwrap();
+                    waitlist.promoted_quantity += 1;
+                    if self.waitlisted_accounts.remove(&QuizChain::get_quiz_by_user(quiz_id, promoted_account_id.clone())) {
+                        self.internal_add_player(quiz_id, quiz, promoted_account_id.clone());
+                        log!("Account {} promoted from the waitlist of quiz {}", promoted_account_id, quiz_id);
+                        break;
+                    }
+                }
+                self.waitlists.insert(&quiz_id, &waitlist);
+            }
+        }
+    }
+}
@@ -2,6 +2,8 @@ import 'regenerator-runtime/runtime'
 
 const contract = require('./rest-api-test-utils');
 const utils = require('./utils');
+const SHA256 = require('crypto-js/sha256');
+const {KeyPair} = require('near-api-js');
 
 const alice = "grant.testnet";
 const bob = "place.testnet";
@@ -10,6 +12,42 @@ const reward = 1;
 const service_fee_ratio = 0.01;
 
 const near = new contract(contract_id);
+// fungible token, NFT and registry views of eligibility gates, deploy contract/out/mock.wasm built with contract/mock/build.sh
+const mock_contract_id = process.env.MOCK_CONTRACT_NAME;
+const mock = new contract(mock_contract_id);
+
+const secret = "77777";
+const success_hash = "d35fee8b00d489a548f54b180c973c75b8c7b0c9483f5d01f1336c0ad1c701e9";
+const salt = "8d9a0f4c27b6e13a";
+
+const getHash = (text) => SHA256(text).toString();
+
+const textOptions = (contents) => contents.map(content => ({content, "kind": "Text"}));
+
+const createQuiz = async (params, account_id = alice) => parseInt(await near.call("create_quiz",
+    {
+        title: "Test QUIZ",
+        finality_type: "Direct",
+        restart_allowed: false,
+        rewards: [{"amount": utils.ConvertToNear(reward)}],
+        ...params
+    }, {
+        account_id,
+        tokens: utils.ConvertToNear(reward + reward * service_fee_ratio),
+        log_errors: true,
+        return_value: true
+    }));
+
+const activateQuiz = (quiz_id, quiz_success_hash) => near.call("activate_quiz", {
+    quiz_id,
+    secret,
+    success_hash_commitment: getHash(quiz_success_hash + salt)
+}, {account_id: alice, log_errors: true});
+
+const sleep = (ms) => new Promise(resolve => setTimeout(resolve, ms));
+
+// block timestamps are in nanoseconds
+const getTimestamp = (seconds_from_now) => (Date.now() + seconds_from_now * 1000) * 1000000;
 
 describe("Contract set", () => {
     test("Contract is not null " + contract_id, async () => {
@@ -33,8 +71,7 @@ describe("Contract set", () => {
 let quiz_id = -1;
 describe("Quiz", () => {
     test('Create Quiz', async () => {
-        let active_quizzes_1 = await near.view("get_active_quizzes", {}, {});
-        let active_quizzes_qty_1 = active_quizzes_1.length;
+        let active_quizzes_qty_1 = await near.view("get_active_quizzes_count", {}, {});
 
         quiz_id = await near.call("create_quiz",
             {
@@ -74,8 +111,8 @@ describe("Quiz", () => {
 
         let activate_quiz = await near.call("activate_quiz", {
             quiz_id,
-            secret: "77777",
-            success_hash: "d35fee8b00d489a548f54b180c973c75b8c7b0c9483f5d01f1336c0ad1c701e9"
+            secret,
+            success_hash_commitment: getHash(success_hash + salt)
         }, {
             account_id: alice,
             log_errors: true
@@ -84,9 +121,9 @@ describe("Quiz", () => {
 
         quiz = await near.view("get_quiz", {quiz_id}, {});
         expect(quiz.status).toBe("InProgress");
+        expect(quiz.success_hash).toBe(null);
 
-        let active_quizzes_2 = await near.view("get_active_quizzes", {}, {});
-        let active_quizzes_qty_2 = active_quizzes_2.length;
+        let active_quizzes_qty_2 = await near.view("get_active_quizzes_count", {}, {});
 
         expect(active_quizzes_qty_2 - active_quizzes_qty_1).toBe(1);
     });
@@ -144,13 +181,1387 @@ describe("Quiz", () => {
         expect(send_answer_3.type).not.toBe('FunctionCallError');
 
         let game_3 = await near.view("get_game", {quiz_id, account_id: alice}, {});
-        expect(game_3.current_hash).toBe('d35fee8b00d489a548f54b180c973c75b8c7b0c9483f5d01f1336c0ad1c701e9');
+        expect(game_3.current_hash).toBe(success_hash);
         expect(game_3.answers_quantity).toBe(3);
 
         let quiz = await near.view("get_quiz", {quiz_id}, {});
+        expect(quiz.distributed_rewards.length).toBe(0);
+
+        let reveal_success_hash = await near.call("reveal_success_hash", {
+            quiz_id,
+            success_hash,
+            salt
+        }, {
+            account_id: bob,
+            log_errors: true
+        });
+        expect(reveal_success_hash.type).not.toBe('FunctionCallError');
+
+        quiz = await near.view("get_quiz", {quiz_id}, {});
+        expect(quiz.status).toBe("Finished");
         expect(quiz.distributed_rewards.length).toBeGreaterThan(0);
         expect(quiz.distributed_rewards[0].winner_account_id).toBe(alice);
     });
 });
 
+describe("Direct quiz enumeration", () => {
+    const options = [["Зима", "Весна", "Осень"], ["2", "4", "6"]];
+    const enumeration_success_hash = getHash(getHash(getHash(secret) + "осень") + "246");
+    let enumeration_quiz_id = -1;
+
+    test('Answers can not be confirmed with on-chain data', async () => {
+        enumeration_quiz_id = await createQuiz({
+            title: "Enumeration QUIZ",
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?"},
+                {"kind": "MultipleChoice", "content": "Какие цифры четные"}
+            ],
+            all_question_options: options.map(textOptions),
+            secret,
+            success_hash_commitment: getHash(enumeration_success_hash + salt)
+        });
+        expect(enumeration_quiz_id).toBeGreaterThan(-1);
+
+        // bob finishes the game with a wrong guess and looks for any feedback about it
+        await near.call("start_game", {quiz_id: enumeration_quiz_id}, {account_id: bob, log_errors: true});
+        const send_answers = await near.call("send_answers", {
+            quiz_id: enumeration_quiz_id,
+            from_question_id: 0,
+            answers: [{question_option_ids: [0]}, {question_option_ids: [0, 1, 2]}]
+        }, {account_id: bob, log_errors: true});
+        expect(send_answers.type).not.toBe('FunctionCallError');
+
+        const quiz = await near.view("get_quiz", {quiz_id: enumeration_quiz_id}, {});
+        const game = await near.view("get_game", {quiz_id: enumeration_quiz_id, account_id: bob}, {});
+        expect(quiz.status).toBe("InProgress");
+        expect(quiz.success_hash).toBe(null);
+        expect(quiz.distributed_rewards.length).toBe(0);
+        expect(game.correct_answers).toBe(0);
+
+        const on_chain_values = [quiz.secret, quiz.success_hash_commitment, game.current_hash].filter(value => value);
+        const seed = getHash(secret);
+        let candidates = [];
+        for (const one_choice of options[0]) {
+            for (let mask = 1; mask < (1 << options[1].length); mask++) {
+                const multiple_choice = options[1].filter((_, index) => mask & (1 << index)).join("");
+                candidates.push(getHash(getHash(seed + one_choice.toLowerCase()) + multiple_choice.toLowerCase()));
+            }
+        }
+
+        // the commitment is salted, so no candidate confirms itself with anything stored on chain
+        const confirmed_candidates = candidates.filter(candidate =>
+            on_chain_values.some(value => getHash(candidate + value) === quiz.success_hash_commitment) ||
+            getHash(candidate) === quiz.success_hash_commitment);
+        expect(confirmed_candidates).toEqual([]);
+        expect(getHash(enumeration_success_hash + salt)).toBe(quiz.success_hash_commitment);
+    });
+
+    test('Plain success hash is rejected for Direct quiz', async () => {
+        const create_quiz = await near.call("create_quiz",
+            {
+                title: "Enumeration QUIZ",
+                finality_type: "Direct",
+                restart_allowed: false,
+                questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+                all_question_options: [textOptions(options[0])],
+                rewards: [{"amount": utils.ConvertToNear(reward)}],
+                secret,
+                success_hash: getHash(getHash(secret) + "осень")
+            }, {
+                account_id: alice,
+                tokens: utils.ConvertToNear(reward + reward * service_fee_ratio)
+            });
+        expect(create_quiz.type).toBe('FunctionCallError');
+    });
+});
+
+describe("Locked quiz editing", () => {
+    test('Edit questions and rewards before activation', async () => {
+        const edited_quiz_id = parseInt(await near.call("create_quiz",
+            {
+                title: "Draft QUIZ",
+                finality_type: "Direct",
+                restart_allowed: false,
+                questions: [
+                    {"kind": "OneChoice", "content": "Какое сейчас время года?"},
+                    {"kind": "Text", "content": "Столица США"}
+                ],
+                all_question_options: [
+                    [{"content": "Зима", "kind": "Text"}, {"content": "Весна", "kind": "Text"}],
+                    []
+                ],
+                rewards: [{"amount": utils.ConvertToNear(reward)}]
+            }, {
+                account_id: alice,
+                tokens: utils.ConvertToNear(reward + reward * service_fee_ratio),
+                log_errors: true,
+                return_value: true
+            }));
+        expect(edited_quiz_id).toBeGreaterThan(-1);
+
+        const insert_question = await near.call("insert_question", {
+            quiz_id: edited_quiz_id,
+            question_id: 0,
+            question: {"kind": "MultipleChoice", "content": "Какие цифры четные"},
+            question_options: [{"content": "2", "kind": "Text"}, {"content": "3", "kind": "Text"}, {"content": "4", "kind": "Text"}]
+        }, {
+            account_id: alice,
+            log_errors: true
+        });
+        expect(insert_question.type).not.toBe('FunctionCallError');
+
+        const remove_question = await near.call("remove_question", {
+            quiz_id: edited_quiz_id,
+            question_id: 2
+        }, {
+            account_id: alice,
+            log_errors: true
+        });
+        expect(remove_question.type).not.toBe('FunctionCallError');
+
+        const update_rewards = await near.call("update_rewards", {
+            quiz_id: edited_quiz_id,
+            rewards: [{"amount": utils.ConvertToNear(reward / 2)}, {"amount": utils.ConvertToNear(reward / 2)}]
+        }, {
+            account_id: alice,
+            log_errors: true
+        });
+        expect(update_rewards.type).not.toBe('FunctionCallError');
+
+        const bob_update_rewards = await near.call("update_rewards", {
+            quiz_id: edited_quiz_id,
+            rewards: [{"amount": utils.ConvertToNear(reward)}]
+        }, {
+            account_id: bob
+        });
+        expect(bob_update_rewards.type).toBe('FunctionCallError');
+
+        const quiz = await near.view("get_quiz", {quiz_id: edited_quiz_id}, {});
+        expect(quiz.status).toBe("Locked");
+        expect(quiz.total_questions).toBe(2);
+        expect(quiz.questions[0].question.content).toBe("Какие цифры четные");
+        expect(quiz.questions[0].question_options.length).toBe(3);
+        expect(quiz.questions[1].question_options.length).toBe(2);
+        expect(quiz.available_rewards.length).toBe(2);
+    });
+});
+
+describe("Locked quiz translations", () => {
+    test('Translations follow inserted and removed questions', async () => {
+        const translated_quiz_id = await createQuiz({
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?"},
+                {"kind": "OneChoice", "content": "Какая цифра четная"}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), textOptions(["3", "4"])]
+        });
+        await near.call("set_quiz_translation", {
+            quiz_id: translated_quiz_id,
+            language: "en",
+            translation: {
+                title: "Test QUIZ",
+                description: null,
+                questions: [
+                    {content: "What season is it now?", hint: null, question_options: ["Winter", "Autumn"]},
+                    {content: "Which digit is even", hint: null, question_options: ["3", "4"]}
+                ]
+            }
+        }, {account_id: alice, log_errors: true});
+
+        await near.call("insert_question", {
+            quiz_id: translated_quiz_id,
+            question_id: 0,
+            question: {"kind": "OneChoice", "content": "Столица США"},
+            question_options: textOptions(["Вашингтон", "Нью-Йорк", "Бостон"])
+        }, {account_id: alice, log_errors: true});
+        await near.call("remove_question", {quiz_id: translated_quiz_id, question_id: 1}, {account_id: alice, log_errors: true});
+
+        const translation = await near.view("get_quiz_translation", {quiz_id: translated_quiz_id, language: "en"}, {});
+        expect(translation.questions.length).toBe(2);
+        expect(translation.questions[0].content).toBe("Столица США");
+        expect(translation.questions[0].question_options).toEqual(["Вашингтон", "Нью-Йорк", "Бостон"]);
+        expect(translation.questions[1].content).toBe("Which digit is even");
+
+        const questions = await near.view("get_questions_by_quiz", {quiz_id: translated_quiz_id, language: "en"}, {});
+        expect(questions[1].question_options[1].content).toBe("4");
+
+        const activate_quiz = await activateQuiz(translated_quiz_id, getHash(getHash(getHash(secret) + "вашингтон") + "4"));
+        expect(activate_quiz.type).not.toBe('FunctionCallError');
+    });
+});
+
+describe("Revisable answers", () => {
+    test('Answers of timed questions can not be revised', async () => {
+        const revisable_quiz_id = await createQuiz({
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?", "time_limit": 600},
+                {"kind": "OneChoice", "content": "Какая цифра четная"}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), textOptions(["3", "4"])]
+        });
+        expect(revisable_quiz_id).toBeGreaterThan(-1);
+
+        const set_revisable_answers = await near.call("set_revisable_answers", {
+            quiz_id: revisable_quiz_id,
+            revisable_answers: true
+        }, {account_id: alice, log_errors: true});
+        expect(set_revisable_answers.type).not.toBe('FunctionCallError');
+
+        const activate_quiz = await activateQuiz(revisable_quiz_id, getHash(getHash(getHash(secret) + "осень") + "4"));
+        expect(activate_quiz.type).not.toBe('FunctionCallError');
+
+        await near.call("start_game", {quiz_id: revisable_quiz_id}, {account_id: bob, log_errors: true});
+        const send_answers = await near.call("send_answers", {
+            quiz_id: revisable_quiz_id,
+            from_question_id: 0,
+            answers: [{question_option_ids: [0]}, {question_option_ids: [0]}]
+        }, {account_id: bob, log_errors: true});
+        expect(send_answers.type).not.toBe('FunctionCallError');
+
+        const revise_timed_answer = await near.call("revise_answer", {
+            quiz_id: revisable_quiz_id,
+            question_id: 0,
+            question_option_ids: [1]
+        }, {account_id: bob});
+        expect(revise_timed_answer.type).toBe('FunctionCallError');
+
+        const revise_answer = await near.call("revise_answer", {
+            quiz_id: revisable_quiz_id,
+            question_id: 1,
+            question_option_ids: [1]
+        }, {account_id: bob, log_errors: true});
+        expect(revise_answer.type).not.toBe('FunctionCallError');
+
+        const answers = await near.view("get_answers", {quiz_id: revisable_quiz_id, account_id: bob}, {});
+        expect(answers[0].selected_option_ids).toEqual([0]);
+        expect(answers[1].selected_option_ids).toEqual([1]);
+    });
+});
+
+describe("Player limits", () => {
+    test('Waitlisted account is promoted when a player abandons the game', async () => {
+        const limited_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_player_limits", {
+            quiz_id: limited_quiz_id,
+            max_players: 1,
+            waitlist_enabled: true,
+            player_timeout: null
+        }, {account_id: alice, log_errors: true});
+        await activateQuiz(limited_quiz_id, getHash(getHash(secret) + "осень"));
+
+        await near.call("start_game", {quiz_id: limited_quiz_id}, {account_id: bob, log_errors: true});
+        await near.call("start_game", {quiz_id: limited_quiz_id}, {account_id: alice, log_errors: true});
+        expect(await near.view("get_players", {quiz_id: limited_quiz_id, from_index: 0, limit: 10}, {})).toEqual([bob]);
+        expect(await near.view("get_waitlist", {quiz_id: limited_quiz_id, from_index: 0, limit: 10}, {})).toEqual([alice]);
+
+        const abandon_game = await near.call("abandon_game", {quiz_id: limited_quiz_id}, {account_id: bob, log_errors: true});
+        expect(abandon_game.type).not.toBe('FunctionCallError');
+
+        expect(await near.view("get_players", {quiz_id: limited_quiz_id, from_index: 0, limit: 10}, {})).toEqual([alice]);
+        expect(await near.view("get_waitlist", {quiz_id: limited_quiz_id, from_index: 0, limit: 10}, {})).toEqual([]);
+
+        const restart_after_release = await near.call("start_game", {quiz_id: limited_quiz_id}, {account_id: bob});
+        expect(restart_after_release.type).toBe('FunctionCallError');
+    });
+
+    test('Committed player is not released after timeout', async () => {
+        const commit_reveal_quiz_id = await createQuiz({
+            finality_type: "CommitReveal",
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_player_limits", {
+            quiz_id: commit_reveal_quiz_id,
+            max_players: 2,
+            waitlist_enabled: false,
+            player_timeout: 1
+        }, {account_id: alice, log_errors: true});
+        const answers_hash = getHash(getHash(secret) + "осень");
+        await activateQuiz(commit_reveal_quiz_id, answers_hash);
+
+        await near.call("start_game", {quiz_id: commit_reveal_quiz_id}, {account_id: bob, log_errors: true});
+        const commit_answers = await near.call("commit_answers", {
+            quiz_id: commit_reveal_quiz_id,
+            hash: getHash(bob + answers_hash + "nonce")
+        }, {account_id: bob, log_errors: true});
+        expect(commit_answers.type).not.toBe('FunctionCallError');
+
+        await sleep(2000);
+        const release_player = await near.call("release_timed_out_player", {
+            quiz_id: commit_reveal_quiz_id,
+            account_id: bob
+        }, {account_id: alice});
+        expect(release_player.type).toBe('FunctionCallError');
+
+        const abandon_game = await near.call("abandon_game", {quiz_id: commit_reveal_quiz_id}, {account_id: bob});
+        expect(abandon_game.type).toBe('FunctionCallError');
+
+        const game = await near.view("get_game", {quiz_id: commit_reveal_quiz_id, account_id: bob}, {});
+        expect(game).not.toBe(null);
+    });
+});
+
+describe("Question pool", () => {
+    test('Pool answers are verified when they are sent', async () => {
+        const pool_quiz_id = await createQuiz({
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?", "answer_hash": getHash(secret + "осень")},
+                {"kind": "OneChoice", "content": "Какое время года после лета?", "answer_hash": getHash(secret + "осень")}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_question_pool", {quiz_id: pool_quiz_id, questions_per_game: 1}, {account_id: alice, log_errors: true});
+        const committed_activation = await activateQuiz(pool_quiz_id, getHash("question pool"));
+        expect(committed_activation.type).toBe('FunctionCallError');
+
+        const activate_quiz = await near.call("activate_quiz", {quiz_id: pool_quiz_id, secret}, {account_id: alice, log_errors: true});
+        expect(activate_quiz.type).not.toBe('FunctionCallError');
+
+        let quiz = await near.view("get_quiz", {quiz_id: pool_quiz_id}, {});
+        expect(quiz.secret).toBe(null);
+        expect(quiz.questions.every(question => question.question.answer_hash === null)).toBe(true);
+
+        await near.call("start_game", {quiz_id: pool_quiz_id}, {account_id: alice, log_errors: true});
+        await near.call("send_answers", {
+            quiz_id: pool_quiz_id,
+            from_question_id: (await near.view("get_game_questions", {quiz_id: pool_quiz_id, account_id: alice}, {}))[0].id,
+            answers: [{question_option_ids: [0]}]
+        }, {account_id: alice, log_errors: true});
+        expect((await near.view("get_game", {quiz_id: pool_quiz_id, account_id: alice}, {})).correct_answers).toBe(0);
+        quiz = await near.view("get_quiz", {quiz_id: pool_quiz_id}, {});
+        expect(quiz.distributed_rewards.length).toBe(0);
+
+        await near.call("start_game", {quiz_id: pool_quiz_id}, {account_id: bob, log_errors: true});
+        const send_answers = await near.call("send_answers", {
+            quiz_id: pool_quiz_id,
+            from_question_id: (await near.view("get_game_questions", {quiz_id: pool_quiz_id, account_id: bob}, {}))[0].id,
+            answers: [{question_option_ids: [1]}]
+        }, {account_id: bob, log_errors: true});
+        expect(send_answers.type).not.toBe('FunctionCallError');
+        expect((await near.view("get_game", {quiz_id: pool_quiz_id, account_id: bob}, {})).correct_answers).toBe(1);
+
+        // the reward is won without the owner being online
+        quiz = await near.view("get_quiz", {quiz_id: pool_quiz_id}, {});
+        expect(quiz.status).toBe("Finished");
+        expect(quiz.distributed_rewards[0].winner_account_id).toBe(bob);
+    });
+});
+
+describe("Accepted answers", () => {
+    test('Accepted answers are hidden and matched only with the revealed salt', async () => {
+        const accepted_answer_hashes = [getHash(salt + "paris"), getHash(salt + "париж")];
+        const aliases_success_hash = getHash(getHash(secret) + accepted_answer_hashes[0]);
+        const aliases_quiz_id = await createQuiz({
+            questions: [{
+                "kind": "Text",
+                "content": "Столица Франции",
+                "text_normalization": {
+                    "trim": true,
+                    "collapse_whitespace": true,
+                    "strip_punctuation": true,
+                    "unicode_normalization": true,
+                    "fold_diacritics": false
+                },
+                accepted_answer_hashes
+            }],
+            all_question_options: [[]]
+        });
+        const activate_quiz = await activateQuiz(aliases_quiz_id, aliases_success_hash);
+        expect(activate_quiz.type).not.toBe('FunctionCallError');
+
+        const quiz = await near.view("get_quiz", {quiz_id: aliases_quiz_id}, {});
+        expect(quiz.secret).toBe(null);
+        expect(quiz.questions[0].question.accepted_answer_hashes).toBe(null);
+
+        await near.call("start_game", {quiz_id: aliases_quiz_id}, {account_id: bob, log_errors: true});
+        const send_answer = await near.call("send_answer", {
+            quiz_id: aliases_quiz_id,
+            question_id: 0,
+            question_option_text: "  Париж. "
+        }, {account_id: bob, log_errors: true});
+        expect(send_answer.type).not.toBe('FunctionCallError');
+
+        // the alias isn't mapped before the reveal, so the game hash gives no feedback
+        const game = await near.view("get_game", {quiz_id: aliases_quiz_id, account_id: bob}, {});
+        expect(game.current_hash).toBe(getHash(getHash(secret) + "париж"));
+        expect(accepted_answer_hashes).not.toContain(getHash(secret + "париж"));
+
+        await near.call("reveal_success_hash", {
+            quiz_id: aliases_quiz_id,
+            success_hash: aliases_success_hash,
+            salt
+        }, {account_id: alice, log_errors: true});
+        expect((await near.view("get_quiz", {quiz_id: aliases_quiz_id}, {})).status).toBe("InProgress");
+
+        const verify_game = await near.call("verify_game", {quiz_id: aliases_quiz_id, account_id: bob}, {account_id: alice, log_errors: true});
+        expect(verify_game.type).not.toBe('FunctionCallError');
+    });
+
+    test('Accepted answers are rejected for DelayedReveal quiz', async () => {
+        const delayed_quiz_id = await createQuiz({
+            finality_type: "DelayedReveal",
+            questions: [{"kind": "Text", "content": "Столица Франции", "accepted_answer_hashes": [getHash(salt + "paris")]}],
+            all_question_options: [[]]
+        });
+        const activate_quiz = await near.call("activate_quiz", {
+            quiz_id: delayed_quiz_id,
+            secret,
+            success_hash: getHash(getHash(secret) + "paris")
+        }, {account_id: alice});
+        expect(activate_quiz.type).toBe('FunctionCallError');
+    });
+});
+
+describe("Owner cancellation", () => {
+    const cancelableQuiz = async (finality_type) => {
+        const cancelable_quiz_id = await createQuiz({
+            finality_type,
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_cancel_grace_period", {quiz_id: cancelable_quiz_id, cancel_grace_period: 1}, {account_id: alice, log_errors: true});
+        await activateQuiz(cancelable_quiz_id, getHash(getHash(secret) + "осень"));
+        return cancelable_quiz_id;
+    };
+
+    test('Quiz without players is cancelled after the grace period', async () => {
+        const cancelable_quiz_id = await cancelableQuiz("Direct");
+
+        const bob_cancel_quiz = await near.call("cancel_quiz", {quiz_id: cancelable_quiz_id}, {account_id: bob});
+        expect(bob_cancel_quiz.type).toBe('FunctionCallError');
+
+        const cancel_quiz = await near.call("cancel_quiz", {quiz_id: cancelable_quiz_id}, {account_id: alice, log_errors: true});
+        expect(cancel_quiz.type).not.toBe('FunctionCallError');
+        expect((await near.view("get_quiz", {quiz_id: cancelable_quiz_id}, {})).status).toBe("Cancelled");
+    });
+
+    test('CommitReveal quiz with committed answers is not cancelled', async () => {
+        const cancelable_quiz_id = await cancelableQuiz("CommitReveal");
+
+        await near.call("start_game", {quiz_id: cancelable_quiz_id}, {account_id: bob, log_errors: true});
+        await near.call("commit_answers", {
+            quiz_id: cancelable_quiz_id,
+            hash: getHash(bob + getHash(getHash(secret) + "осень") + "nonce")
+        }, {account_id: bob, log_errors: true});
+
+        const cancel_quiz = await near.call("cancel_quiz", {quiz_id: cancelable_quiz_id}, {account_id: alice});
+        expect(cancel_quiz.type).toBe('FunctionCallError');
+        expect((await near.view("get_quiz", {quiz_id: cancelable_quiz_id}, {})).status).toBe("InProgress");
+    });
+
+    test('Direct quiz with players awaiting the reveal is not cancelled', async () => {
+        const cancelable_quiz_id = await cancelableQuiz("Direct");
+
+        await near.call("start_game", {quiz_id: cancelable_quiz_id}, {account_id: bob, log_errors: true});
+        await near.call("send_answer", {
+            quiz_id: cancelable_quiz_id,
+            question_id: 0,
+            question_option_ids: [1]
+        }, {account_id: bob, log_errors: true});
+
+        const cancel_quiz = await near.call("cancel_quiz", {quiz_id: cancelable_quiz_id}, {account_id: alice});
+        expect(cancel_quiz.type).toBe('FunctionCallError');
+        expect((await near.view("get_quiz", {quiz_id: cancelable_quiz_id}, {})).status).toBe("InProgress");
+    });
+
+    test('Funded quiz is updated by its owner only and refunded to the sponsor on cancel', async () => {
+        const funded_quiz_id = parseInt(await near.call("create_quiz_for_account", {quiz_owner_id: alice}, {
+            account_id: bob,
+            tokens: utils.ConvertToNear(reward + reward * service_fee_ratio),
+            log_errors: true,
+            return_value: true
+        }));
+        const funded_quiz = {
+            quiz_id: funded_quiz_id,
+            title: "Funded QUIZ",
+            finality_type: "Direct",
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])],
+            rewards: [{"amount": utils.ConvertToNear(reward)}],
+            restart_allowed: false
+        };
+
+        const bob_update = await near.call("update_funded_quiz", funded_quiz, {account_id: bob});
+        expect(bob_update.type).toBe('FunctionCallError');
+
+        await near.call("update_funded_quiz", funded_quiz, {account_id: alice, log_errors: true});
+        const quiz = await near.view("get_quiz", {quiz_id: funded_quiz_id}, {});
+        expect(quiz.owner_id).toBe(alice);
+        expect(quiz.status).toBe("Locked");
+
+        const bob_balance = await near.accountNearBalance(bob);
+        await near.call("cancel_quiz", {quiz_id: funded_quiz_id}, {account_id: alice, log_errors: true});
+        expect((await near.view("get_quiz", {quiz_id: funded_quiz_id}, {})).status).toBe("Cancelled");
+        expect(await near.accountNearBalance(bob)).toBeGreaterThan(bob_balance + reward / 2);
+    });
+});
+
+describe("Eligibility gates", () => {
+    const registry_gate = {"Registry": {"registry_account_id": mock_contract_id, "method_name": "is_registered"}};
+
+    const gatedQuiz = async (eligibility_gates, max_players = null) => {
+        const gated_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_eligibility_gates", {quiz_id: gated_quiz_id, eligibility_gates}, {account_id: alice, log_errors: true});
+        if (max_players) {
+            await near.call("set_player_limits", {
+                quiz_id: gated_quiz_id,
+                max_players,
+                waitlist_enabled: false,
+                player_timeout: null
+            }, {account_id: alice, log_errors: true});
+        }
+        await activateQuiz(gated_quiz_id, getHash(getHash(secret) + "осень"));
+        return gated_quiz_id;
+    };
+
+    test('Init mock', async () => {
+        expect(mock_contract_id).not.toBe(undefined);
+        await mock.call("new", {}, {account_id: mock_contract_id});
+        await mock.call("unregister", {account_id: bob}, {account_id: mock_contract_id, log_errors: true});
+        await mock.call("register", {account_id: alice}, {account_id: mock_contract_id, log_errors: true});
+        expect(await mock.view("is_registered", {account_id: alice}, {})).toBe(true);
+        await near.call("whitelist_token", {token_id: mock_contract_id}, {account_id: contract_id, log_errors: true});
+    });
+
+    test('Game is not started for account which fails a gate', async () => {
+        const gated_quiz_id = await gatedQuiz([registry_gate]);
+
+        const start_game = await near.call("start_game", {quiz_id: gated_quiz_id}, {account_id: bob, log_errors: true});
+        expect(start_game.type).not.toBe('FunctionCallError');
+        expect(await near.view("get_game", {quiz_id: gated_quiz_id, account_id: bob}, {})).toBe(null);
+
+        await near.call("start_game", {quiz_id: gated_quiz_id}, {account_id: alice, log_errors: true});
+        expect(await near.view("get_game", {quiz_id: gated_quiz_id, account_id: alice}, {})).not.toBe(null);
+    });
+
+    test('All gates have to be passed', async () => {
+        const gated_quiz_id = await gatedQuiz([
+            registry_gate,
+            {"FtBalance": {"token_account_id": mock_contract_id, "min_balance": "100"}},
+            {"NftOwnership": {"nft_account_id": mock_contract_id}}
+        ]);
+        await mock.call("set_ft_balance", {account_id: alice, balance: "100"}, {account_id: mock_contract_id, log_errors: true});
+        await mock.call("set_nft_supply", {account_id: alice, supply: "0"}, {account_id: mock_contract_id, log_errors: true});
+
+        await near.call("start_game", {quiz_id: gated_quiz_id}, {account_id: alice, log_errors: true});
+        expect(await near.view("get_game", {quiz_id: gated_quiz_id, account_id: alice}, {})).toBe(null);
+
+        await mock.call("set_nft_supply", {account_id: alice, supply: "1"}, {account_id: mock_contract_id, log_errors: true});
+        await near.call("start_game", {quiz_id: gated_quiz_id}, {account_id: alice, log_errors: true});
+        expect(await near.view("get_game", {quiz_id: gated_quiz_id, account_id: alice}, {})).not.toBe(null);
+    });
+
+    test('Account which passed the gates after the quiz became full is refunded', async () => {
+        await mock.call("register", {account_id: bob}, {account_id: mock_contract_id, log_errors: true});
+        const gated_quiz_id = await gatedQuiz([registry_gate], 1);
+
+        // both accounts pass the checks before the gates, only one of them gets the place
+        const start_games = await Promise.all([alice, bob].map(account_id =>
+            near.call("start_game", {quiz_id: gated_quiz_id}, {account_id, log_errors: true})));
+        start_games.forEach(start_game => expect(start_game.type).not.toBe('FunctionCallError'));
+
+        const players = await near.view("get_players", {quiz_id: gated_quiz_id, from_index: 0, limit: 10}, {});
+        expect(players.length).toBe(1);
+        expect(await near.view("get_waitlist", {quiz_id: gated_quiz_id, from_index: 0, limit: 10}, {})).toEqual([]);
+    });
+});
+
+describe("Quiz indexes", () => {
+    const tag = "index-" + Date.now();
+    const language = "ru-" + Date.now();
+    const translation_language = "en-" + Date.now();
+    const getIds = async (method, params) => (await near.view(method, {...params, from_index: 0, limit: 1000}, {})).map(quiz => quiz.id);
+
+    test('Quiz and its clone are indexed by tags, languages and status', async () => {
+        const indexed_quiz_id = await createQuiz({
+            language,
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_quiz_tags", {quiz_id: indexed_quiz_id, tags: [tag]}, {account_id: alice, log_errors: true});
+        const set_quiz_translation = await near.call("set_quiz_translation", {
+            quiz_id: indexed_quiz_id,
+            language: translation_language,
+            translation: {
+                title: "Test QUIZ",
+                description: null,
+                questions: [{content: "What season is it now?", hint: null, question_options: ["Winter", "Autumn"]}]
+            }
+        }, {account_id: alice, log_errors: true});
+        expect(set_quiz_translation.type).not.toBe('FunctionCallError');
+
+        expect(await getIds("get_quizzes_by_tag", {tag})).toContain(indexed_quiz_id);
+        expect(await getIds("get_quizzes_by_language", {language})).toContain(indexed_quiz_id);
+        expect(await getIds("get_quizzes_by_language", {language: translation_language})).toContain(indexed_quiz_id);
+
+        const cloned_quiz_id = parseInt(await near.call("clone_quiz", {
+            source_quiz_id: indexed_quiz_id,
+            finality_type: "Direct",
+            rewards: [{"amount": utils.ConvertToNear(reward)}],
+            restart_allowed: false
+        }, {
+            account_id: alice,
+            tokens: utils.ConvertToNear(reward + reward * service_fee_ratio),
+            log_errors: true,
+            return_value: true
+        }));
+        expect(cloned_quiz_id).toBeGreaterThan(indexed_quiz_id);
+        expect(await getIds("get_quizzes_by_language", {language: translation_language})).toContain(cloned_quiz_id);
+
+        await activateQuiz(indexed_quiz_id, getHash(getHash(secret) + "осень"));
+        expect(await getIds("get_quizzes_by_status", {status: "InProgress"})).toContain(indexed_quiz_id);
+        expect(await getIds("get_quizzes_by_status", {status: "Locked"})).not.toContain(indexed_quiz_id);
+    });
+});
+
+describe("Active quizzes", () => {
+    test('Filtered quizzes are found after the first page of the index', async () => {
+        const delayed_reveal_quiz_id = await createQuiz({
+            finality_type: "DelayedReveal",
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("activate_quiz", {quiz_id: delayed_reveal_quiz_id, secret}, {account_id: alice, log_errors: true});
+        for (let i = 0; i < 2; i++) {
+            const direct_quiz_id = await createQuiz({
+                questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+                all_question_options: [textOptions(["Зима", "Осень"])]
+            });
+            await activateQuiz(direct_quiz_id, getHash(getHash(secret) + "осень"));
+        }
+
+        const filter = {finality_type: "DelayedReveal"};
+        const page = await near.view("get_active_quizzes", {from_index: 0, limit: 1, filter, sort: "Newest"}, {});
+        expect(page.quizzes.map(quiz => quiz.id)).toEqual([delayed_reveal_quiz_id]);
+        expect(page.next_index).toBe(3);
+
+        const direct_page = await near.view("get_active_quizzes", {from_index: 0, limit: 2, filter: {finality_type: "Direct"}, sort: "Newest"}, {});
+        expect(direct_page.quizzes.length).toBe(2);
+        expect(direct_page.next_index).toBe(2);
+    });
+});
+
+describe("Quiz cloning", () => {
+    const cloneQuiz = (source_quiz_id, account_id, params = {}) => near.call("clone_quiz", {
+        source_quiz_id,
+        finality_type: "Direct",
+        rewards: [{"amount": utils.ConvertToNear(reward)}],
+        restart_allowed: false,
+        ...params
+    }, {
+        account_id,
+        tokens: utils.ConvertToNear(reward + reward * service_fee_ratio),
+        return_value: true
+    });
+
+    test('Only licensed accounts clone the quiz', async () => {
+        const source_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+
+        const owner_only_clone = await cloneQuiz(source_quiz_id, bob);
+        expect(owner_only_clone.type).toBe('FunctionCallError');
+
+        await near.call("set_clone_policy", {quiz_id: source_quiz_id, clone_policy: "Licensed"}, {account_id: alice, log_errors: true});
+        await near.call("add_clone_license", {quiz_id: source_quiz_id, account_id: bob}, {account_id: alice, log_errors: true});
+        expect(await near.view("can_clone_quiz", {quiz_id: source_quiz_id, account_id: bob}, {})).toBe(true);
+
+        const cloned_quiz_id = parseInt(await cloneQuiz(source_quiz_id, bob, {title: "Cloned QUIZ"}));
+        expect(cloned_quiz_id).toBeGreaterThan(source_quiz_id);
+
+        const cloned_quiz = await near.view("get_quiz", {quiz_id: cloned_quiz_id}, {});
+        expect(cloned_quiz.owner_id).toBe(bob);
+        expect(cloned_quiz.title).toBe("Cloned QUIZ");
+        expect(cloned_quiz.status).toBe("Locked");
+        expect(cloned_quiz.questions[0].question.content).toBe("Какое сейчас время года?");
+    });
+
+    test('Question pool is cloned with answer hashes by the owner only', async () => {
+        const answer_hash = getHash(secret + "осень");
+        const source_quiz_id = await createQuiz({
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?", answer_hash},
+                {"kind": "OneChoice", "content": "Какое время года после лета?", answer_hash}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_question_pool", {quiz_id: source_quiz_id, questions_per_game: 1}, {account_id: alice, log_errors: true});
+        await near.call("set_shuffle_options", {quiz_id: source_quiz_id, shuffle_options: true}, {account_id: alice, log_errors: true});
+        await near.call("set_clone_policy", {quiz_id: source_quiz_id, clone_policy: "Public"}, {account_id: alice, log_errors: true});
+
+        const bob_clone = await cloneQuiz(source_quiz_id, bob);
+        expect(bob_clone.type).toBe('FunctionCallError');
+
+        const commit_reveal_clone = await cloneQuiz(source_quiz_id, alice, {finality_type: "CommitReveal"});
+        expect(commit_reveal_clone.type).toBe('FunctionCallError');
+
+        const cloned_quiz_id = parseInt(await cloneQuiz(source_quiz_id, alice));
+        expect(cloned_quiz_id).toBeGreaterThan(source_quiz_id);
+
+        const cloned_quiz = await near.view("get_quiz", {quiz_id: cloned_quiz_id}, {});
+        expect(cloned_quiz.questions_per_game).toBe(1);
+        expect(cloned_quiz.shuffle_options).toBe(true);
+
+        // answer hashes are copied, so the clone is activated with the secret of the source quiz
+        const activate_quiz = await near.call("activate_quiz", {quiz_id: cloned_quiz_id, secret}, {account_id: alice, log_errors: true});
+        expect(activate_quiz.type).not.toBe('FunctionCallError');
+    });
+});
+
+describe("Commit-reveal answers", () => {
+    test('Committed answers are revealed by their owner only', async () => {
+        const answers = [{selected_option_ids: [1], selected_text: null, selected_pairs: null},
+            {selected_option_ids: null, selected_text: "Вашингтон", selected_pairs: null}];
+        const answers_hash = getHash(getHash(getHash(secret) + "осень") + "вашингтон");
+        const commit_reveal_quiz_id = await createQuiz({
+            finality_type: "CommitReveal",
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?"},
+                {"kind": "Text", "content": "Столица США"}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), []]
+        });
+        await activateQuiz(commit_reveal_quiz_id, answers_hash);
+
+        await near.call("start_game", {quiz_id: commit_reveal_quiz_id}, {account_id: bob, log_errors: true});
+        await near.call("start_game", {quiz_id: commit_reveal_quiz_id}, {account_id: alice, log_errors: true});
+
+        const send_answer = await near.call("send_answer", {
+            quiz_id: commit_reveal_quiz_id,
+            question_id: 0,
+            question_option_ids: [1]
+        }, {account_id: bob});
+        expect(send_answer.type).toBe('FunctionCallError');
+
+        // alice copies the commitment of bob
+        const commitment_hash = getHash(bob + answers_hash + "nonce");
+        for (const account_id of [bob, alice]) {
+            const commit_answers = await near.call("commit_answers", {quiz_id: commit_reveal_quiz_id, hash: commitment_hash},
+                {account_id, log_errors: true});
+            expect(commit_answers.type).not.toBe('FunctionCallError');
+        }
+
+        const early_reveal = await near.call("reveal_committed_answers", {quiz_id: commit_reveal_quiz_id, answers, nonce: "nonce"}, {account_id: bob});
+        expect(early_reveal.type).toBe('FunctionCallError');
+
+        await near.call("reveal_success_hash", {
+            quiz_id: commit_reveal_quiz_id,
+            success_hash: answers_hash,
+            salt
+        }, {account_id: alice, log_errors: true});
+
+        const late_commit = await near.call("commit_answers", {quiz_id: commit_reveal_quiz_id, hash: commitment_hash}, {account_id: bob});
+        expect(late_commit.type).toBe('FunctionCallError');
+
+        const bob_reveal = await near.call("reveal_committed_answers", {quiz_id: commit_reveal_quiz_id, answers, nonce: "nonce"},
+            {account_id: bob, log_errors: true});
+        expect(bob_reveal.type).not.toBe('FunctionCallError');
+        expect((await near.view("get_answer_commitment", {quiz_id: commit_reveal_quiz_id, account_id: bob}, {})).revealed).toBe(true);
+
+        const alice_reveal = await near.call("reveal_committed_answers", {quiz_id: commit_reveal_quiz_id, answers, nonce: "nonce"}, {account_id: alice});
+        expect(alice_reveal.type).toBe('FunctionCallError');
+        expect((await near.view("get_answer_commitment", {quiz_id: commit_reveal_quiz_id, account_id: alice}, {})).revealed).toBe(false);
+
+        const finalize_quiz = await near.call("finalize_quiz", {quiz_id: commit_reveal_quiz_id}, {account_id: bob});
+        expect(finalize_quiz.type).toBe('FunctionCallError');
+    });
+
+    test('Player keeps the place of the first commitment', async () => {
+        const answers_hash = getHash(getHash(secret) + "осень");
+        const commit_reveal_quiz_id = await createQuiz({
+            finality_type: "CommitReveal",
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await activateQuiz(commit_reveal_quiz_id, answers_hash);
+        await near.call("start_game", {quiz_id: commit_reveal_quiz_id}, {account_id: bob, log_errors: true});
+
+        await near.call("commit_answers", {
+            quiz_id: commit_reveal_quiz_id,
+            hash: getHash(bob + getHash(getHash(secret) + "зима") + "nonce")
+        }, {account_id: bob, log_errors: true});
+        const first_commitment = await near.view("get_answer_commitment", {quiz_id: commit_reveal_quiz_id, account_id: bob}, {});
+
+        const commitment_hash = getHash(bob + answers_hash + "nonce");
+        await near.call("commit_answers", {quiz_id: commit_reveal_quiz_id, hash: commitment_hash}, {account_id: bob, log_errors: true});
+        const commitment = await near.view("get_answer_commitment", {quiz_id: commit_reveal_quiz_id, account_id: bob}, {});
+        expect(commitment.hash).toBe(commitment_hash);
+        expect(commitment.timestamp).toBe(first_commitment.timestamp);
+    });
+});
+
+describe("Quiz time window", () => {
+    const windowQuiz = (params) => createQuiz({
+        questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+        all_question_options: [textOptions(["Зима", "Осень"])],
+        ...params
+    });
+
+    test('Quiz with end before the start is rejected', async () => {
+        const create_quiz = await near.call("create_quiz", {
+            title: "Test QUIZ",
+            finality_type: "Direct",
+            restart_allowed: false,
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])],
+            rewards: [{"amount": utils.ConvertToNear(reward)}],
+            starts_at: getTimestamp(3600),
+            ends_at: getTimestamp(60)
+        }, {
+            account_id: alice,
+            tokens: utils.ConvertToNear(reward + reward * service_fee_ratio)
+        });
+        expect(create_quiz.type).toBe('FunctionCallError');
+    });
+
+    test('Games are started only inside the time window', async () => {
+        const future_quiz_id = await windowQuiz({starts_at: getTimestamp(3600)});
+        await activateQuiz(future_quiz_id, getHash(getHash(secret) + "осень"));
+        const early_start = await near.call("start_game", {quiz_id: future_quiz_id}, {account_id: bob});
+        expect(early_start.type).toBe('FunctionCallError');
+
+        const ending_quiz_id = await windowQuiz({ends_at: getTimestamp(20)});
+        await activateQuiz(ending_quiz_id, getHash(getHash(secret) + "осень"));
+        await near.call("start_game", {quiz_id: ending_quiz_id}, {account_id: bob, log_errors: true});
+
+        await sleep(25000);
+        const late_answer = await near.call("send_answer", {
+            quiz_id: ending_quiz_id,
+            question_id: 0,
+            question_option_ids: [1]
+        }, {account_id: bob});
+        expect(late_answer.type).toBe('FunctionCallError');
+
+        const late_start = await near.call("start_game", {quiz_id: ending_quiz_id}, {account_id: alice});
+        expect(late_start.type).toBe('FunctionCallError');
+
+        // the owner still has a day to reveal the success hash
+        const finalize_quiz = await near.call("finalize_quiz", {quiz_id: ending_quiz_id}, {account_id: bob});
+        expect(finalize_quiz.type).toBe('FunctionCallError');
+    }, 60000);
+});
+
+describe("Question time limits", () => {
+    test('Answers sent after the time limit are counted as wrong', async () => {
+        const timed_quiz_id = await createQuiz({
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?", "time_limit": 3},
+                {"kind": "OneChoice", "content": "Какая цифра четная", "time_limit": 600}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), textOptions(["3", "4"])]
+        });
+        await activateQuiz(timed_quiz_id, getHash(getHash(getHash(secret) + "осень") + "4"));
+
+        await near.call("start_game", {quiz_id: timed_quiz_id}, {account_id: bob, log_errors: true});
+        expect(await near.view("get_question_time_left", {quiz_id: timed_quiz_id, account_id: bob}, {})).toBeLessThanOrEqual(3);
+
+        await sleep(6000);
+        const late_answer = await near.call("send_answer", {
+            quiz_id: timed_quiz_id,
+            question_id: 0,
+            question_option_ids: [1]
+        }, {account_id: bob, log_errors: true});
+        expect(late_answer.type).not.toBe('FunctionCallError');
+
+        const answer = await near.view("get_answer", {quiz_id: timed_quiz_id, account_id: bob, question_id: 0}, {});
+        expect(answer.selected_option_ids).toBe(null);
+        const game = await near.view("get_game", {quiz_id: timed_quiz_id, account_id: bob}, {});
+        expect(game.current_hash).toBe(getHash(getHash(secret) + "#late"));
+
+        // the time of the next question starts with the previous answer
+        expect(await near.view("get_question_time_left", {quiz_id: timed_quiz_id, account_id: bob}, {})).toBeGreaterThan(500);
+    }, 30000);
+});
+
+describe("Shuffled options", () => {
+    test('Shown option ids of the player are mapped to the canonical options', async () => {
+        const seasons = ["Зима", "Весна", "Лето", "Осень"];
+        const shuffled_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(seasons)]
+        });
+        await near.call("set_shuffle_options", {quiz_id: shuffled_quiz_id, shuffle_options: true}, {account_id: alice, log_errors: true});
+        await activateQuiz(shuffled_quiz_id, getHash(getHash(secret) + "осень"));
+
+        await near.call("start_game", {quiz_id: shuffled_quiz_id}, {account_id: bob, log_errors: true});
+        const question_options = await near.view("get_game_question_options", {quiz_id: shuffled_quiz_id, question_id: 0, account_id: bob}, {});
+        expect(question_options.map(question_option => question_option.id)).toEqual([0, 1, 2, 3]);
+        expect(question_options.map(question_option => question_option.content).sort()).toEqual([...seasons].sort());
+
+        const shown_option_id = question_options.find(question_option => question_option.content === "Осень").id;
+        await near.call("send_answer", {
+            quiz_id: shuffled_quiz_id,
+            question_id: 0,
+            question_option_ids: [shown_option_id]
+        }, {account_id: bob, log_errors: true});
+
+        expect((await near.view("get_answer", {quiz_id: shuffled_quiz_id, account_id: bob, question_id: 0}, {})).selected_option_ids).toEqual([3]);
+        expect((await near.view("get_game", {quiz_id: shuffled_quiz_id, account_id: bob}, {})).current_hash)
+            .toBe(getHash(getHash(secret) + "осень"));
+    });
+});
+
+describe("Numeric questions", () => {
+    const numeric_question = {
+        "kind": "Numeric",
+        "content": "Сколько дней в високосном году?",
+        // one decimal place, answers from 366.0 to 367.9 share the bucket
+        "numeric_settings": {"decimals": 1, "bucket_width": 20, "bucket_offset": 0}
+    };
+
+    test('Numbers in the same bucket give the same answers hash', async () => {
+        const numeric_quiz_id = await createQuiz({
+            questions: [numeric_question],
+            all_question_options: [[]]
+        });
+        await activateQuiz(numeric_quiz_id, getHash(getHash(secret) + "183"));
+
+        await near.call("start_game", {quiz_id: numeric_quiz_id}, {account_id: alice, log_errors: true});
+        const illegal_answer = await near.call("send_answer", {
+            quiz_id: numeric_quiz_id,
+            question_id: 0,
+            question_option_text: "366.25"
+        }, {account_id: alice});
+        expect(illegal_answer.type).toBe('FunctionCallError');
+
+        await near.call("start_game", {quiz_id: numeric_quiz_id}, {account_id: bob, log_errors: true});
+        await near.call("send_answer", {
+            quiz_id: numeric_quiz_id,
+            question_id: 0,
+            question_option_text: " 366.5"
+        }, {account_id: bob, log_errors: true});
+
+        expect((await near.view("get_answer", {quiz_id: numeric_quiz_id, account_id: bob, question_id: 0}, {})).selected_text).toBe("366.5");
+        expect((await near.view("get_game", {quiz_id: numeric_quiz_id, account_id: bob}, {})).current_hash)
+            .toBe(getHash(getHash(secret) + "183"));
+    });
+
+    test('Numeric question without settings is rejected', async () => {
+        const create_quiz = await near.call("create_quiz", {
+            title: "Test QUIZ",
+            finality_type: "Direct",
+            restart_allowed: false,
+            questions: [{"kind": "Numeric", "content": "Сколько дней в високосном году?"}],
+            all_question_options: [[]],
+            rewards: [{"amount": utils.ConvertToNear(reward)}]
+        }, {
+            account_id: alice,
+            tokens: utils.ConvertToNear(reward + reward * service_fee_ratio)
+        });
+        expect(create_quiz.type).toBe('FunctionCallError');
+    });
+});
+
+describe("Ordering questions", () => {
+    test('Options are hashed in the order of the answer', async () => {
+        const ordering_quiz_id = await createQuiz({
+            questions: [{"kind": "Ordering", "content": "Расположите времена года начиная с весны"}],
+            all_question_options: [textOptions(["Зима", "Весна", "Лето"])]
+        });
+        await activateQuiz(ordering_quiz_id, getHash(getHash(secret) + "весналетозима"));
+
+        await near.call("start_game", {quiz_id: ordering_quiz_id}, {account_id: bob, log_errors: true});
+        for (const question_option_ids of [[1, 2], [1, 1, 2]]) {
+            const illegal_order = await near.call("send_answer", {
+                quiz_id: ordering_quiz_id,
+                question_id: 0,
+                question_option_ids
+            }, {account_id: bob});
+            expect(illegal_order.type).toBe('FunctionCallError');
+        }
+
+        await near.call("send_answer", {
+            quiz_id: ordering_quiz_id,
+            question_id: 0,
+            question_option_ids: [1, 2, 0]
+        }, {account_id: bob, log_errors: true});
 
+        expect((await near.view("get_answer", {quiz_id: ordering_quiz_id, account_id: bob, question_id: 0}, {})).selected_option_ids)
+            .toEqual([1, 2, 0]);
+        expect((await near.view("get_game", {quiz_id: ordering_quiz_id, account_id: bob}, {})).current_hash)
+            .toBe(getHash(getHash(secret) + "весналетозима"));
+    });
+});
+
+describe("Matching questions", () => {
+    test('Pairs of left and right options are matched', async () => {
+        const matching_options = [
+            {"content": "Франция", "kind": "Text", "column": "Left"},
+            {"content": "Германия", "kind": "Text", "column": "Left"},
+            {"content": "Берлин", "kind": "Text", "column": "Right"},
+            {"content": "Париж", "kind": "Text", "column": "Right"}
+        ];
+        const matching_success_hash = getHash(getHash(secret) + "франция=париж;германия=берлин;");
+        const matching_quiz_id = await createQuiz({
+            questions: [{"kind": "Matching", "content": "Сопоставьте страны и столицы"}],
+            all_question_options: [matching_options]
+        });
+        await activateQuiz(matching_quiz_id, matching_success_hash);
+
+        await near.call("start_game", {quiz_id: matching_quiz_id}, {account_id: bob, log_errors: true});
+        for (const question_option_pairs of [[[2, 0], [3, 1]], [[0, 3]], [[0, 3], [1, 3]]]) {
+            const illegal_pairs = await near.call("send_answer", {
+                quiz_id: matching_quiz_id,
+                question_id: 0,
+                question_option_pairs
+            }, {account_id: bob});
+            expect(illegal_pairs.type).toBe('FunctionCallError');
+        }
+
+        await near.call("send_answer", {
+            quiz_id: matching_quiz_id,
+            question_id: 0,
+            question_option_pairs: [[1, 2], [0, 3]]
+        }, {account_id: bob, log_errors: true});
+
+        expect((await near.view("get_answer", {quiz_id: matching_quiz_id, account_id: bob, question_id: 0}, {})).selected_pairs)
+            .toEqual([[0, 3], [1, 2]]);
+        expect((await near.view("get_game", {quiz_id: matching_quiz_id, account_id: bob}, {})).current_hash).toBe(matching_success_hash);
+    });
+
+    test('Matching options without a column are rejected', async () => {
+        const create_quiz = await near.call("create_quiz", {
+            title: "Test QUIZ",
+            finality_type: "Direct",
+            restart_allowed: false,
+            questions: [{"kind": "Matching", "content": "Сопоставьте страны и столицы"}],
+            all_question_options: [textOptions(["Франция", "Париж"])],
+            rewards: [{"amount": utils.ConvertToNear(reward)}]
+        }, {
+            account_id: alice,
+            tokens: utils.ConvertToNear(reward + reward * service_fee_ratio)
+        });
+        expect(create_quiz.type).toBe('FunctionCallError');
+    });
+});
+
+describe("Quiz translations", () => {
+    const translation = {
+        title: "Test QUIZ",
+        description: null,
+        questions: [{content: "What season is it now?", hint: "Look outside", question_options: ["Winter", "Autumn"]}]
+    };
+
+    test('Players of all languages answer with the same options', async () => {
+        const translated_quiz_id = await createQuiz({
+            language: "ru",
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+
+        const base_language_translation = await near.call("set_quiz_translation",
+            {quiz_id: translated_quiz_id, language: "ru", translation}, {account_id: alice});
+        expect(base_language_translation.type).toBe('FunctionCallError');
+
+        const incomplete_translation = await near.call("set_quiz_translation", {
+            quiz_id: translated_quiz_id,
+            language: "en",
+            translation: {...translation, questions: [{...translation.questions[0], question_options: ["Winter"]}]}
+        }, {account_id: alice});
+        expect(incomplete_translation.type).toBe('FunctionCallError');
+
+        for (const language of ["en", "de"]) {
+            await near.call("set_quiz_translation", {quiz_id: translated_quiz_id, language, translation}, {account_id: alice, log_errors: true});
+        }
+        await near.call("remove_quiz_translation", {quiz_id: translated_quiz_id, language: "de"}, {account_id: alice, log_errors: true});
+        expect((await near.view("get_quiz", {quiz_id: translated_quiz_id}, {})).languages).toEqual(["en"]);
+
+        await activateQuiz(translated_quiz_id, getHash(getHash(secret) + "осень"));
+        const late_translation = await near.call("set_quiz_translation",
+            {quiz_id: translated_quiz_id, language: "de", translation}, {account_id: alice});
+        expect(late_translation.type).toBe('FunctionCallError');
+
+        await near.call("start_game", {quiz_id: translated_quiz_id}, {account_id: bob, log_errors: true});
+        const questions = await near.view("get_game_questions", {quiz_id: translated_quiz_id, account_id: bob, language: "en"}, {});
+        expect(questions[0].question.content).toBe("What season is it now?");
+        expect(questions[0].question.hint).toBe("Look outside");
+        expect(questions[0].question_options.map(question_option => question_option.content)).toEqual(["Winter", "Autumn"]);
+
+        // answers are hashed with texts of the base language
+        await near.call("send_answer", {quiz_id: translated_quiz_id, question_id: 0, question_option_ids: [1]}, {account_id: bob, log_errors: true});
+        expect((await near.view("get_game", {quiz_id: translated_quiz_id, account_id: bob}, {})).current_hash)
+            .toBe(getHash(getHash(secret) + "осень"));
+    });
+});
+
+describe("Quiz statuses", () => {
+    test('Cancelled quiz is final', async () => {
+        const cancelled_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        const cancel_quiz = await near.call("cancel_quiz", {quiz_id: cancelled_quiz_id}, {account_id: alice, log_errors: true});
+        expect(cancel_quiz.type).not.toBe('FunctionCallError');
+
+        const quiz = await near.view("get_quiz", {quiz_id: cancelled_quiz_id}, {});
+        expect(quiz.status).toBe("Cancelled");
+        expect(quiz.available_rewards.length).toBe(0);
+        const cancelled_quiz_ids = (await near.view("get_quizzes_by_status", {status: "Cancelled", from_index: 0, limit: 1000}, {}))
+            .map(cancelled_quiz => cancelled_quiz.id);
+        expect(cancelled_quiz_ids).toContain(cancelled_quiz_id);
+
+        const activate_quiz = await near.call("activate_quiz", {
+            quiz_id: cancelled_quiz_id,
+            secret,
+            success_hash_commitment: getHash(getHash(getHash(secret) + "осень") + salt)
+        }, {account_id: alice});
+        expect(activate_quiz.type).toBe('FunctionCallError');
+
+        const start_game = await near.call("start_game", {quiz_id: cancelled_quiz_id}, {account_id: bob});
+        expect(start_game.type).toBe('FunctionCallError');
+
+        const cancel_again = await near.call("cancel_quiz", {quiz_id: cancelled_quiz_id}, {account_id: alice});
+        expect(cancel_again.type).toBe('FunctionCallError');
+
+        const finalize_quiz = await near.call("finalize_quiz", {quiz_id: cancelled_quiz_id}, {account_id: alice});
+        expect(finalize_quiz.type).toBe('FunctionCallError');
+    });
+
+    test('Quiz in progress is not finalized before the end', async () => {
+        const ending_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])],
+            ends_at: getTimestamp(3600)
+        });
+        await activateQuiz(ending_quiz_id, getHash(getHash(secret) + "осень"));
+
+        const finalize_quiz = await near.call("finalize_quiz", {quiz_id: ending_quiz_id}, {account_id: bob});
+        expect(finalize_quiz.type).toBe('FunctionCallError');
+        expect((await near.view("get_quiz", {quiz_id: ending_quiz_id}, {})).status).toBe("InProgress");
+    });
+});
+
+describe("Scheduled activation", () => {
+    test('Anyone who knows the secret activates the quiz at the scheduled time', async () => {
+        const scheduled_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        const activation_at = getTimestamp(15);
+        const schedule_quiz_activation = await near.call("schedule_quiz_activation", {
+            quiz_id: scheduled_quiz_id,
+            activation_at,
+            secret_commitment: getHash(secret),
+            success_hash_commitment: getHash(getHash(getHash(secret) + "осень") + salt)
+        }, {account_id: alice, log_errors: true});
+        expect(schedule_quiz_activation.type).not.toBe('FunctionCallError');
+        expect((await near.view("get_quiz", {quiz_id: scheduled_quiz_id}, {})).scheduled_activation_at).toBe(activation_at);
+
+        const bob_schedule = await near.call("schedule_quiz_activation", {
+            quiz_id: scheduled_quiz_id,
+            activation_at,
+            secret_commitment: getHash(secret),
+            success_hash_commitment: getHash(getHash(getHash(secret) + "зима") + salt)
+        }, {account_id: bob});
+        expect(bob_schedule.type).toBe('FunctionCallError');
+
+        const early_activation = await near.call("activate_scheduled_quiz", {quiz_id: scheduled_quiz_id, secret}, {account_id: bob});
+        expect(early_activation.type).toBe('FunctionCallError');
+
+        await sleep(20000);
+        const wrong_secret_activation = await near.call("activate_scheduled_quiz", {quiz_id: scheduled_quiz_id, secret: "12345"}, {account_id: bob});
+        expect(wrong_secret_activation.type).toBe('FunctionCallError');
+
+        const activate_scheduled_quiz = await near.call("activate_scheduled_quiz", {quiz_id: scheduled_quiz_id, secret},
+            {account_id: bob, log_errors: true});
+        expect(activate_scheduled_quiz.type).not.toBe('FunctionCallError');
+
+        const quiz = await near.view("get_quiz", {quiz_id: scheduled_quiz_id}, {});
+        expect(quiz.status).toBe("InProgress");
+        expect(quiz.scheduled_activation_at).toBe(null);
+    }, 60000);
+
+    test('Scheduled activation is cancelled by the owner', async () => {
+        const scheduled_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("schedule_quiz_activation", {
+            quiz_id: scheduled_quiz_id,
+            activation_at: getTimestamp(5),
+            secret_commitment: getHash(secret),
+            success_hash_commitment: getHash(getHash(getHash(secret) + "осень") + salt)
+        }, {account_id: alice, log_errors: true});
+        await near.call("cancel_quiz_activation", {quiz_id: scheduled_quiz_id}, {account_id: alice, log_errors: true});
+
+        await sleep(10000);
+        const activate_scheduled_quiz = await near.call("activate_scheduled_quiz", {quiz_id: scheduled_quiz_id, secret}, {account_id: bob});
+        expect(activate_scheduled_quiz.type).toBe('FunctionCallError');
+        expect((await near.view("get_quiz", {quiz_id: scheduled_quiz_id}, {})).status).toBe("Locked");
+    }, 30000);
+});
+
+describe("Batch answers", () => {
+    test('Batch of answers gives the same hash as answers sent one by one', async () => {
+        const batch_success_hash = getHash(getHash(getHash(getHash(secret) + "осень") + "вашингтон") + "4");
+        const batch_quiz_id = await createQuiz({
+            questions: [
+                {"kind": "OneChoice", "content": "Какое сейчас время года?"},
+                {"kind": "Text", "content": "Столица США"},
+                {"kind": "OneChoice", "content": "Какая цифра четная"}
+            ],
+            all_question_options: [textOptions(["Зима", "Осень"]), [], textOptions(["3", "4"])]
+        });
+        await activateQuiz(batch_quiz_id, batch_success_hash);
+
+        await near.call("start_game", {quiz_id: batch_quiz_id}, {account_id: bob, log_errors: true});
+        const wrong_index = await near.call("send_answers", {
+            quiz_id: batch_quiz_id,
+            from_question_id: 1,
+            answers: [{question_option_text: "Вашингтон"}]
+        }, {account_id: bob});
+        expect(wrong_index.type).toBe('FunctionCallError');
+
+        const empty_batch = await near.call("send_answers", {quiz_id: batch_quiz_id, from_question_id: 0, answers: []}, {account_id: bob});
+        expect(empty_batch.type).toBe('FunctionCallError');
+
+        await near.call("send_answers", {
+            quiz_id: batch_quiz_id,
+            from_question_id: 0,
+            answers: [{question_option_ids: [1]}, {question_option_text: "Вашингтон"}]
+        }, {account_id: bob, log_errors: true});
+        expect((await near.view("get_game", {quiz_id: batch_quiz_id, account_id: bob}, {})).answers_quantity).toBe(2);
+
+        const too_many_answers = await near.call("send_answers", {
+            quiz_id: batch_quiz_id,
+            from_question_id: 2,
+            answers: [{question_option_ids: [1]}, {question_option_ids: [1]}]
+        }, {account_id: bob});
+        expect(too_many_answers.type).toBe('FunctionCallError');
+
+        await near.call("send_answer", {quiz_id: batch_quiz_id, question_id: 2, question_option_ids: [1]}, {account_id: bob, log_errors: true});
+
+        const game = await near.view("get_game", {quiz_id: batch_quiz_id, account_id: bob}, {});
+        expect(game.answers_quantity).toBe(3);
+        expect(game.current_hash).toBe(batch_success_hash);
+    });
+});
+
+describe("Entry fees", () => {
+    const entry_fee = 0.1;
+
+    test('Entry fees grow the prize pool and are refunded on cancel', async () => {
+        const paid_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        const illegal_rates = await near.call("set_entry_fee", {
+            quiz_id: paid_quiz_id,
+            entry_fee: {amount: utils.ConvertToNear(entry_fee), prize_pool_rate: 8000, owner_rate: 2000, service_rate: 0}
+        }, {account_id: alice});
+        expect(illegal_rates.type).toBe('FunctionCallError');
+
+        await near.call("set_entry_fee", {
+            quiz_id: paid_quiz_id,
+            entry_fee: {amount: utils.ConvertToNear(entry_fee), prize_pool_rate: 8000, owner_rate: 1900, service_rate: 100}
+        }, {account_id: alice, log_errors: true});
+        await near.call("set_cancel_grace_period", {quiz_id: paid_quiz_id, cancel_grace_period: 1}, {account_id: alice, log_errors: true});
+        await activateQuiz(paid_quiz_id, getHash(getHash(secret) + "осень"));
+
+        const free_start = await near.call("start_game", {quiz_id: paid_quiz_id}, {account_id: bob});
+        expect(free_start.type).toBe('FunctionCallError');
+        const wrong_amount_start = await near.call("start_game", {quiz_id: paid_quiz_id}, {account_id: bob, tokens: utils.ConvertToNear(entry_fee / 2)});
+        expect(wrong_amount_start.type).toBe('FunctionCallError');
+
+        const start_game = await near.call("start_game", {quiz_id: paid_quiz_id}, {account_id: bob, tokens: utils.ConvertToNear(entry_fee), log_errors: true});
+        expect(start_game.type).not.toBe('FunctionCallError');
+        expect(await near.view("get_entry_fee_payment", {quiz_id: paid_quiz_id, account_id: bob}, {})).toBe(utils.ConvertToNear(entry_fee));
+
+        const quiz = await near.view("get_quiz", {quiz_id: paid_quiz_id}, {});
+        expect(quiz.entry_fee.entry_fees_quantity).toBe(1);
+        expect(quiz.entry_fee.prize_pool).toBe(utils.ConvertToNear(entry_fee * 0.8));
+
+        const early_refund = await near.call("claim_entry_fee_refund", {quiz_id: paid_quiz_id}, {account_id: bob});
+        expect(early_refund.type).toBe('FunctionCallError');
+
+        await near.call("cancel_quiz", {quiz_id: paid_quiz_id}, {account_id: alice, log_errors: true});
+        const withdraw_entry_fees = await near.call("withdraw_entry_fees", {quiz_id: paid_quiz_id}, {account_id: alice});
+        expect(withdraw_entry_fees.type).toBe('FunctionCallError');
+
+        const claim_entry_fee_refund = await near.call("claim_entry_fee_refund", {quiz_id: paid_quiz_id}, {account_id: bob, log_errors: true});
+        expect(claim_entry_fee_refund.type).not.toBe('FunctionCallError');
+        expect(await near.view("get_entry_fee_payment", {quiz_id: paid_quiz_id, account_id: bob}, {})).toBe(null);
+    });
+});
+
+describe("Invitations", () => {
+    const invitation_key_pair = KeyPair.fromRandom("ed25519");
+
+    // seconds are scaled to nanoseconds without rounding, so the signed message matches the contract one
+    const getInvitation = (quiz_id, account_id, seconds_from_now, key_pair = invitation_key_pair) => {
+        const expires_at = Math.floor(Date.now() / 1000 + seconds_from_now) * 1000000000;
+        const message = `${quiz_id}:${account_id}:${expires_at}`;
+        return {
+            expires_at,
+            signature: Buffer.from(key_pair.sign(Buffer.from(message)).signature).toString("base64")
+        };
+    };
+
+    test('Invite-only quiz is played with a valid invitation only', async () => {
+        const invite_only_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_invitation_key", {
+            quiz_id: invite_only_quiz_id,
+            invitation_key: invitation_key_pair.getPublicKey().toString()
+        }, {account_id: alice, log_errors: true});
+        await activateQuiz(invite_only_quiz_id, getHash(getHash(secret) + "осень"));
+
+        const invalid_invitations = [
+            undefined,
+            getInvitation(invite_only_quiz_id, alice, 3600),
+            getInvitation(invite_only_quiz_id, bob, -60),
+            getInvitation(invite_only_quiz_id, bob, 3600, KeyPair.fromRandom("ed25519")),
+            {...getInvitation(invite_only_quiz_id, bob, 3600), expires_at: Math.floor(Date.now() / 1000 + 7200) * 1000000000}
+        ];
+        for (const invitation of invalid_invitations) {
+            const start_game = await near.call("start_game", {quiz_id: invite_only_quiz_id, invitation}, {account_id: bob});
+            expect(start_game.type).toBe('FunctionCallError');
+        }
+
+        const start_game = await near.call("start_game", {
+            quiz_id: invite_only_quiz_id,
+            invitation: getInvitation(invite_only_quiz_id, bob, 3600)
+        }, {account_id: bob, log_errors: true});
+        expect(start_game.type).not.toBe('FunctionCallError');
+        expect(await near.view("get_game", {quiz_id: invite_only_quiz_id, account_id: bob}, {})).not.toBe(null);
+    });
+});
+
+describe("Allowlist", () => {
+    test('Allowlist-only quiz is played by allowlisted accounts', async () => {
+        const allowlist_quiz_id = await createQuiz({
+            questions: [{"kind": "OneChoice", "content": "Какое сейчас время года?"}],
+            all_question_options: [textOptions(["Зима", "Осень"])]
+        });
+        await near.call("set_allowlist_only", {quiz_id: allowlist_quiz_id, allowlist_only: true}, {account_id: alice, log_errors: true});
+
+        const bob_add_to_allowlist = await near.call("add_to_allowlist", {quiz_id: allowlist_quiz_id, account_ids: [bob]}, {account_id: bob});
+        expect(bob_add_to_allowlist.type).toBe('FunctionCallError');
+
+        await near.call("add_to_allowlist", {quiz_id: allowlist_quiz_id, account_ids: [alice, bob, "extra.testnet"]},
+            {account_id: alice, log_errors: true});
+        await near.call("remove_from_allowlist", {quiz_id: allowlist_quiz_id, account_ids: [alice, "extra.testnet"]},
+            {account_id: alice, log_errors: true});
+        expect(await near.view("get_allowlist_size", {quiz_id: allowlist_quiz_id}, {})).toBe(1);
+        expect(await near.view("get_allowlist", {quiz_id: allowlist_quiz_id, from_index: 0, limit: 10}, {})).toEqual([bob]);
+        expect(await near.view("is_allowlisted", {quiz_id: allowlist_quiz_id, account_id: alice}, {})).toBe(false);
+
+        await activateQuiz(allowlist_quiz_id, getHash(getHash(secret) + "осень"));
+
+        const alice_start_game = await near.call("start_game", {quiz_id: allowlist_quiz_id}, {account_id: alice});
+        expect(alice_start_game.type).toBe('FunctionCallError');
+
+        const bob_start_game = await near.call("start_game", {quiz_id: allowlist_quiz_id}, {account_id: bob, log_errors: true});
+        expect(bob_start_game.type).not.toBe('FunctionCallError');
+        expect(await near.view("get_game", {quiz_id: allowlist_quiz_id, account_id: bob}, {})).not.toBe(null);
+    });
+});